@@ -0,0 +1,222 @@
+//! Derive macro for `rax_nmea::data::INmeaData`.
+//!
+//! See [`NmeaSentence`] for the supported attributes.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Expr, Fields, LitInt, parse_macro_input};
+
+/// Generates getters, an `INmeaData` parser and serde impls for a sentence
+/// struct with named fields.
+///
+/// The parser validates the checksum, skips the `$xxXXX` header and then
+/// reads one field per struct field, in declaration order:
+///
+/// * `#[nmea(talker)]` (or a field named `talker`): receives the talker and
+///   reads nothing.
+/// * `#[nmea(time)]`, `#[nmea(date)]`: `NMEA_TIME`, `NMEA_DATE`.
+/// * `#[nmea(coord)]`, `#[nmea(degree)]`: `NMEA_COORD`, `NMEA_DEGREE`; both
+///   read a value and its hemisphere.
+/// * `#[nmea(rule = EXPR)]`: takes any `IStrFlowRule`; the field is an
+///   `Option` of the rule's output.
+/// * no kind: the field up to the next `,` or `*`, parsed with
+///   `StrParserContext::parse_field` into an `Option<T>`.
+/// * `#[nmea(skip = N)]`: skip `N` fields before reading this one.
+///
+/// Empty fields become `None`. A non-empty field that does not parse is
+/// logged and becomes `None` too, unless the context was switched to
+/// `NumMode::Strict` with `StrParserContext::field_mode`; then it fails with
+/// a `StrParserError` at the field's offset.
+///
+/// Doc comments on fields are copied to their getters.
+///
+/// ```ignore
+/// /// Heading of vehicle.
+/// #[derive(Clone, NmeaSentence)]
+/// pub struct Ths {
+///     talker: Talker,
+///     /// Heading of vehicle (true)
+///     headt: Option<f64>,
+///     /// Mode indicator
+///     mi: Option<PosMode>,
+/// }
+/// ```
+#[proc_macro_derive(NmeaSentence, attributes(nmea))]
+pub fn derive_nmea_sentence(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+enum Kind {
+    Talker,
+    Parse,
+    Rule(TokenStream2),
+}
+
+struct Field {
+    ident: syn::Ident,
+    ty: syn::Type,
+    docs: Vec<syn::Attribute>,
+    kind: Kind,
+    skip: usize,
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<Field> {
+    let ident = field
+        .ident
+        .clone()
+        .ok_or_else(|| syn::Error::new(field.span(), "expected a named field"))?;
+    let mut kind = None;
+    let mut skip = 0;
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("nmea")) {
+        attr.parse_nested_meta(|meta| {
+            let rule = |name: &str| {
+                let rule = format_ident!("{}", name);
+                Kind::Rule(quote!(::rax_nmea::rules::#rule))
+            };
+            let new_kind = if meta.path.is_ident("talker") {
+                Kind::Talker
+            } else if meta.path.is_ident("time") {
+                rule("NMEA_TIME")
+            } else if meta.path.is_ident("date") {
+                rule("NMEA_DATE")
+            } else if meta.path.is_ident("coord") {
+                rule("NMEA_COORD")
+            } else if meta.path.is_ident("degree") {
+                rule("NMEA_DEGREE")
+            } else if meta.path.is_ident("rule") {
+                let expr: Expr = meta.value()?.parse()?;
+                Kind::Rule(quote!(#expr))
+            } else if meta.path.is_ident("skip") {
+                let lit: LitInt = meta.value()?.parse()?;
+                skip = lit.base10_parse()?;
+                return Ok(());
+            } else {
+                return Err(meta.error("unsupported nmea attribute"));
+            };
+            if kind.replace(new_kind).is_some() {
+                return Err(meta.error("only one field kind is allowed"));
+            }
+            Ok(())
+        })?;
+    }
+    let kind = kind.unwrap_or(if ident == "talker" {
+        Kind::Talker
+    } else {
+        Kind::Parse
+    });
+    Ok(Field {
+        docs: field
+            .attrs
+            .iter()
+            .filter(|a| a.path().is_ident("doc"))
+            .cloned()
+            .collect(),
+        ty: field.ty.clone(),
+        ident,
+        kind,
+        skip,
+    })
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(
+            input.span(),
+            "NmeaSentence can only be derived for structs",
+        ));
+    };
+    let Fields::Named(named) = &data.fields else {
+        return Err(syn::Error::new(
+            input.span(),
+            "NmeaSentence requires named fields",
+        ));
+    };
+    let fields = named
+        .named
+        .iter()
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+    let name = &input.ident;
+    let name_str = name.to_string();
+    let private = quote!(::rax_nmea::__private);
+    let rules = quote!(::rax_nmea::rules);
+
+    let getters = fields.iter().map(|f| {
+        let Field {
+            ident, ty, docs, ..
+        } = f;
+        quote! {
+            #(#docs)*
+            pub fn #ident(&self) -> &#ty { &self.#ident }
+        }
+    });
+
+    let parse_fields = fields.iter().map(|f| {
+        let ident = &f.ident;
+        let skips = (0..f.skip).map(|_| quote!(ctx.skip(&#rules::UNTIL_COMMA_OR_STAR_DISCARD);));
+        let take = match &f.kind {
+            Kind::Talker => quote!(talker),
+            Kind::Parse => quote!(ctx.parse_field(&#rules::UNTIL_COMMA_OR_STAR_DISCARD)?),
+            Kind::Rule(rule) => quote!(ctx.take_field(&#rule)?),
+        };
+        quote! {
+            #(#skips)*
+            let #ident = #take;
+        }
+    });
+    let idents: Vec<_> = fields.iter().map(|f| &f.ident).collect();
+    let types: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+
+    Ok(quote! {
+        impl #name {
+            #(#getters)*
+        }
+
+        impl ::rax_nmea::data::INmeaData for #name {
+            fn new(
+                ctx: &mut #private::rax::str_parser::StrParserContext,
+                talker: ::rax_nmea::data::Talker,
+            ) -> #private::miette::Result<Self> {
+                ctx.global(&#rules::NMEA_VALIDATE)?;
+                ctx.skip_strict(&#rules::UNTIL_COMMA_DISCARD)?;
+                #(#parse_fields)*
+                Ok(Self { #(#idents),* })
+            }
+        }
+
+        const _: () = {
+            #[derive(#private::serde::Serialize)]
+            #[serde(crate = "::rax_nmea::__private::serde", rename = #name_str)]
+            struct Ref<'a> {
+                #(#idents: &'a #types),*
+            }
+            #[derive(#private::serde::Deserialize)]
+            #[serde(crate = "::rax_nmea::__private::serde", rename = #name_str)]
+            struct Owned {
+                #(#idents: #types),*
+            }
+            impl #private::serde::Serialize for #name {
+                fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+                where
+                    S: #private::serde::Serializer,
+                {
+                    Ref { #(#idents: &self.#idents),* }.serialize(serializer)
+                }
+            }
+            impl<'de> #private::serde::Deserialize<'de> for #name {
+                fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+                where
+                    D: #private::serde::Deserializer<'de>,
+                {
+                    let Owned { #(#idents),* } = Owned::deserialize(deserializer)?;
+                    Ok(Self { #(#idents),* })
+                }
+            }
+        };
+    })
+}
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 
 use crate::data::{INmeaData, Talker};
 use crate::macros::readonly_struct;
@@ -39,12 +39,14 @@ readonly_struct!(
 impl INmeaData for Dhv {
     fn new(ctx: &mut StrParserContext, talker: Talker) -> miette::Result<Self> {
         ctx.global(&NMEA_VALIDATE)?;
-        let time = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_TIME);
-        let speed3d = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let speed_x = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let speed_y = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let speed_z = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let gdspd = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let time = ctx
+            .skip_strict(&UNTIL_COMMA_DISCARD)?
+            .take_field(&NMEA_TIME)?;
+        let speed3d = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let speed_x = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let speed_y = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let speed_z = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let gdspd = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
 
         Ok(Dhv {
             talker,
@@ -90,6 +92,7 @@ impl fmt::Debug for Dhv {
 mod test {
 
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -108,4 +111,16 @@ mod test {
         assert_eq!(dhv.gdspd.unwrap(), 0.06);
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GNDHV,021150.000,0.03,0.0x6,-0.042,-0.026,0.06*2D";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Dhv::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 23);
+        assert_eq!(err.rule(), "parse::<f64>");
+    }
 }
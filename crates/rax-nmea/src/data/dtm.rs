@@ -1,7 +1,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 use serde::{Deserialize, Serialize};
 
 use crate::data::{INmeaData, Talker};
@@ -57,12 +57,11 @@ impl INmeaData for Dtm {
         ctx.global(&NMEA_VALIDATE)?;
         let datum = ctx
             .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .take(&UNTIL_COMMA_DISCARD)
-            .parse_opt();
-        let sub_datum = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let lat = ctx.take(&NMEA_DEGREE);
-        let lon = ctx.take(&NMEA_DEGREE);
-        let alt = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+            .parse_field(&UNTIL_COMMA_DISCARD)?;
+        let sub_datum = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let lat = ctx.take_field(&NMEA_DEGREE)?;
+        let lon = ctx.take_field(&NMEA_DEGREE)?;
+        let alt = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
 
         Ok(Dtm {
             talker,
@@ -104,6 +103,7 @@ impl fmt::Debug for Dtm {
 mod test {
 
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -120,4 +120,16 @@ mod test {
         assert_eq!(dhv.alt.unwrap(), -47.7);
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPDTM,999,,0.08,N,0.07,E,-4x.7,W84*54";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Dtm::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 26);
+        assert_eq!(err.rule(), "parse::<f64>");
+    }
 }
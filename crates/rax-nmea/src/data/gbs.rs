@@ -1,6 +1,6 @@
 use std::fmt::Debug;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 
 use crate::data::{INmeaData, SystemId, Talker};
 use crate::macros::readonly_struct;
@@ -56,16 +56,18 @@ readonly_struct!(
 
 impl INmeaData for Gbs {
     fn new(ctx: &mut StrParserContext, talker: Talker) -> miette::Result<Self> {
-        let time = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_TIME);
-        let err_lat = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let err_lon = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let err_alt = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let svid = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let prob = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let bias = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let std_dev = ctx.take(&UNTIL_COMMA_OR_STAR_DISCARD).parse_opt();
-        let system_id = ctx.take(&UNTIL_COMMA_OR_STAR_DISCARD).parse_opt();
-        let signal_id = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let time = ctx
+            .skip_strict(&UNTIL_COMMA_DISCARD)?
+            .take_field(&NMEA_TIME)?;
+        let err_lat = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let err_lon = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let err_alt = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let svid = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let prob = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let bias = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let std_dev = ctx.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
+        let system_id = ctx.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
+        let signal_id = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
 
         Ok(Gbs {
             talker,
@@ -124,6 +126,7 @@ impl Debug for Gbs {
 #[cfg(test)]
 mod tests {
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
 
@@ -158,4 +161,16 @@ mod tests {
         assert_eq!(gbs.system_id().unwrap(), SystemId::GPS);
         assert_eq!(gbs.signal_id().unwrap(), 0);
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGBS,235458.00,1.4,1.3,3.1,0x,,-21.4,3.8,1,0*11";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Gbs::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 29);
+        assert_eq!(err.rule(), "parse::<u16>");
+    }
 }
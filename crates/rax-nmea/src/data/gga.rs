@@ -1,7 +1,7 @@
 use std::fmt::{self, Display};
 use std::str::FromStr;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 use serde::{Deserialize, Serialize};
 
 use crate::data::{INmeaData, Talker};
@@ -110,49 +110,51 @@ impl INmeaData for Gga {
         ctx.global(&NMEA_VALIDATE)?;
 
         clerk::debug!("Parsing utc_time...");
-        let time = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_TIME);
+        let time = ctx
+            .skip_strict(&UNTIL_COMMA_DISCARD)?
+            .take_field(&NMEA_TIME)?;
         clerk::debug!("utc_time: {:?}", time);
 
         clerk::debug!("Parsing lat...");
-        let lat = ctx.take(&NMEA_COORD);
+        let lat = ctx.take_field(&NMEA_COORD)?;
         clerk::debug!("lat: {:?}", lat);
 
         clerk::debug!("Parsing lon...");
-        let lon = ctx.take(&NMEA_COORD);
+        let lon = ctx.take_field(&NMEA_COORD)?;
         clerk::debug!("lon: {:?}", lon);
 
         clerk::debug!("Parsing quality...");
-        let quality = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let quality = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!("quality: {:?}", quality);
 
         clerk::debug!("Parsing satellite_count...");
-        let num_sv = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let num_sv = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!("satellite_count: {:?}", num_sv);
 
         clerk::debug!("Parsing hdop...");
-        let hdop = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let hdop = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!("hdop: {:?}", hdop);
 
         clerk::debug!("Parsing altitude...");
-        let alt = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let alt = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!("altitude: {:?}", alt);
 
         clerk::debug!("Skipping char_comma and char_m for altitude units...");
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
 
         clerk::debug!("Parsing geoid_separation...");
-        let sep = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let sep = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!("geoid_separation: {:?}", sep);
 
         clerk::debug!("Skipping char_m for geoid units...");
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
 
         clerk::debug!("Parsing age_of_differential_gps_data...");
-        let diff_age = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let diff_age = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!("age_of_differential_gps_data: {:?}", diff_age);
 
         clerk::debug!("Parsing differential_reference_station_id...");
-        let diff_station = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let diff_station = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
         clerk::debug!("differential_reference_station_id: {:?}", diff_station);
 
         Ok(Gga {
@@ -216,9 +218,22 @@ mod test {
 
     use clerk::{LogLevel, init_log_with_level};
     use float_cmp::assert_approx_eq;
+    use rax::str_parser::{IRule, NumMode, StrParserError};
 
     use super::*;
 
+    #[test]
+    fn test_new_gga_truncated() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGGA*56";
+        let mut ctx = StrParserContext::new();
+        let report = Gga::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 0);
+        assert_eq!(err.rule(), UNTIL_COMMA_DISCARD.name());
+        assert_eq!(err.rest(), s);
+    }
+
     #[test]
     fn test_new_gga1() -> miette::Result<()> {
         init_log_with_level(LogLevel::TRACE);
@@ -242,4 +257,16 @@ mod test {
         assert!(gga.diff_station.is_none());
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGGA,110256,5505.676996,N,03856.028884,E,2,8x,0.7,2135.0,M,14.0,M,,*35";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Gga::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 45);
+        assert_eq!(err.rule(), "parse::<u8>");
+    }
 }
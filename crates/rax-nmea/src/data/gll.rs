@@ -1,67 +1,27 @@
 use std::fmt;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use crate::NmeaSentence;
+use crate::data::{PosMode, Status, Talker};
 
-use crate::data::{INmeaData, PosMode, Status, Talker};
-use crate::macros::readonly_struct;
-use crate::rules::*;
-readonly_struct!(
-    Gll ,
-    "Latitude and longitude, with time of position fix and status",
-    {talker: Talker},
-
-    {
-        lat: Option<f64>,
-        "Latitude, ddmm.mmmm, where dd is degrees and mm.mmmm is minutes. Positive values indicate North, negative values indicate South."
-    },
-    {
-        lon: Option<f64>,
-        "Longitude, dddmm.mmmm, where ddd is degrees and mm.mmmm is minutes. Positive values indicate East, negative values indicate West."
-    },
-    {
-        time: Option<chrono::NaiveTime>,
-        "UTC time of the position fix"
-    },
-    {
-        status: Option<Status>,
-        "Status of the data"
-    },
-    {
-        pos_mode: Option<PosMode>,
-        "FAA mode"
-    }
-);
-impl INmeaData for Gll {
-    fn new(ctx: &mut StrParserContext, talker: Talker) -> miette::Result<Self> {
-        clerk::trace!("Gga::new: sentence='{}'", ctx.full_str());
-
-        ctx.global(&NMEA_VALIDATE)?;
-
-        clerk::debug!("Parsing lat...");
-        let lat = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_COORD);
-        clerk::debug!("lat: {:?}", lat);
-
-        clerk::debug!("Parsing lon...");
-        let lon = ctx.take(&NMEA_COORD);
-        clerk::debug!("lon: {:?}", lon);
-
-        clerk::debug!("Parsing utc_time...");
-        let time = ctx.take(&NMEA_TIME);
-        clerk::debug!("utc_time: {:?}", time);
-
-        let status = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-
-        let pos_mode = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
-
-        Ok(Gll {
-            talker,
-            lat,
-            lon,
-            time,
-            status,
-            pos_mode,
-        })
-    }
+/// Latitude and longitude, with time of position fix and status
+#[derive(Clone, NmeaSentence)]
+pub struct Gll {
+    talker: Talker,
+    /// Latitude, ddmm.mmmm, where dd is degrees and mm.mmmm is minutes.
+    /// Positive values indicate North, negative values indicate South.
+    #[nmea(coord)]
+    lat: Option<f64>,
+    /// Longitude, dddmm.mmmm, where ddd is degrees and mm.mmmm is minutes.
+    /// Positive values indicate East, negative values indicate West.
+    #[nmea(coord)]
+    lon: Option<f64>,
+    /// UTC time of the position fix
+    #[nmea(time)]
+    time: Option<chrono::NaiveTime>,
+    /// Status of the data
+    status: Option<Status>,
+    /// FAA mode
+    pos_mode: Option<PosMode>,
 }
 
 impl fmt::Debug for Gll {
@@ -93,8 +53,10 @@ impl fmt::Debug for Gll {
 mod test {
     use clerk::{LogLevel, init_log_with_level};
     use float_cmp::assert_approx_eq;
+    use rax::str_parser::StrParserContext;
 
     use super::*;
+    use crate::data::INmeaData;
     #[test]
     fn test_new_ggl() -> miette::Result<()> {
         init_log_with_level(LogLevel::TRACE);
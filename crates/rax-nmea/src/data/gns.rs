@@ -1,7 +1,7 @@
 use std::fmt::Debug;
 use std::str::FromStr;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 use serde::{Deserialize, Serialize};
 
 use crate::data::{INmeaData, PosMode, Talker};
@@ -85,21 +85,21 @@ impl INmeaData for Gns {
         ctx.global(&NMEA_VALIDATE)?;
 
         clerk::debug!("Parsing utc_time...");
-        let time = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_TIME);
+        let time = ctx
+            .skip_strict(&UNTIL_COMMA_DISCARD)?
+            .take_field(&NMEA_TIME)?;
         clerk::debug!("utc_time: {:?}", time);
 
         clerk::debug!("Parsing lat...");
-        let lat = ctx.take(&NMEA_COORD);
+        let lat = ctx.take_field(&NMEA_COORD)?;
         clerk::debug!("lat: {:?}", lat);
 
         clerk::debug!("Parsing lon...");
-        let lon = ctx.take(&NMEA_COORD);
+        let lon = ctx.take_field(&NMEA_COORD)?;
         clerk::debug!("lon: {:?}", lon);
 
         clerk::debug!("Parsing mode...");
-        let mode_str = ctx
-            .take(&UNTIL_COMMA_DISCARD)
-            .expect("Mode string should not be empty.");
+        let mode_str = ctx.take_strict(&UNTIL_COMMA_DISCARD)?;
         let pos_mode = mode_str
             .char_indices()
             .filter_map(|(_, c)| PosMode::try_from(&c).ok())
@@ -107,33 +107,33 @@ impl INmeaData for Gns {
         clerk::debug!("mode: {:?}", pos_mode);
 
         clerk::debug!("Parsing satellites...");
-        let num_sv = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let num_sv = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!("satellites: {:?}", num_sv);
 
         clerk::debug!("Parsing hdop...");
-        let hdop = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let hdop = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!("hdop: {:?}", hdop);
 
         clerk::debug!("Parsing altitude...");
-        let alt = ctx.take(&UNTIL_COMMA_OR_STAR_DISCARD).parse_opt();
+        let alt = ctx.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
         clerk::debug!("altitude: {:?}", alt);
 
         clerk::debug!("Parsing goeidal_separation...");
-        let sep = ctx.take(&UNTIL_COMMA_OR_STAR_DISCARD).parse_opt();
+        let sep = ctx.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
         clerk::debug!("goeidal_separation: {:?}", sep);
 
         clerk::debug!("Parsing differential_data_age...");
-        let diff_age = ctx.take(&UNTIL_COMMA_OR_STAR_DISCARD).parse_opt();
+        let diff_age = ctx.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
         clerk::debug!("differential_data_age: {:?}", diff_age);
 
         clerk::debug!("Parsing differential_reference_station_id...");
 
-        let diff_station = ctx.take(&UNTIL_COMMA_OR_STAR_DISCARD).parse_opt();
+        let diff_station = ctx.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
 
         clerk::debug!("differential_reference_station_id: {:?}", diff_station);
 
         clerk::debug!("Parsing navigational_status...");
-        let nav_status = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let nav_status = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
         clerk::debug!("navigational_status: {:?}", nav_status);
 
         Ok(Gns {
@@ -195,6 +195,7 @@ impl Debug for Gns {
 mod test {
     use clerk::{LogLevel, init_log_with_level};
     use float_cmp::assert_approx_eq;
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     use crate::data::{PosMode, Talker};
@@ -241,4 +242,16 @@ mod test {
         assert!(gns.nav_status.is_none());
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGNS,112257.00,3844.2x011,N,00908.43828,W,AN,03,10.5,,*1B";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Gns::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 17);
+        assert_eq!(err.rule(), "NmeaCoord");
+    }
 }
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 
 use crate::data::{INmeaData, Talker};
 use crate::macros::readonly_struct;
@@ -21,8 +21,7 @@ impl INmeaData for Gpq {
         ctx.global(&NMEA_VALIDATE)?;
         let msg_id = ctx
             .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .take(&UNTIL_STAR_DISCARD)
-            .parse_opt();
+            .parse_field(&UNTIL_STAR_DISCARD)?;
 
         Ok(Gpq { talker, msg_id })
     }
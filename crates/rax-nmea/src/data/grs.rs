@@ -1,7 +1,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 use serde::{Deserialize, Serialize};
 
 use crate::data::{INmeaData, SystemId, Talker};
@@ -53,9 +53,11 @@ impl INmeaData for Grs {
     fn new(ctx: &mut StrParserContext, talker: Talker) -> miette::Result<Self> {
         ctx.global(&NMEA_VALIDATE)?;
 
-        let time = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_TIME);
+        let time = ctx
+            .skip_strict(&UNTIL_COMMA_DISCARD)?
+            .take_field(&NMEA_TIME)?;
 
-        let mode = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let mode = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::debug!(
             "Grs::new: utc_time={:?}, grs_residual_mode={:?}",
             time,
@@ -64,15 +66,15 @@ impl INmeaData for Grs {
 
         let mut residual = Vec::with_capacity(12);
         for _ in 0..12 {
-            match ctx.take(&UNTIL_COMMA_DISCARD).parse_opt::<f64>() {
+            match ctx.parse_field::<_, f64>(&UNTIL_COMMA_DISCARD)? {
                 Some(r) => residual.push(r),
                 None => continue,
             }
         }
         clerk::debug!("Grs::new: satellite_residuals={:?}", residual);
 
-        let system_id = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let signal_id = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let system_id = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let signal_id = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
         Ok(Grs {
             talker,
             time,
@@ -116,6 +118,7 @@ mod test {
 
     use clerk::{LogLevel, init_log_with_level};
     use float_cmp::assert_approx_eq;
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -141,4 +144,16 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGRS,220320.0,0,-0.8,-0.2,-0.x,-0.2,0.8,0.6,,,,,,*30";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Grs::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 28);
+        assert_eq!(err.rule(), "parse::<f64>");
+    }
 }
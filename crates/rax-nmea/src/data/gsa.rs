@@ -1,7 +1,7 @@
 use std::fmt;
 use std::str::FromStr;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 use serde::{Deserialize, Serialize};
 
 use crate::data::{INmeaData, SystemId, Talker};
@@ -80,31 +80,30 @@ impl INmeaData for Gsa {
 
         let op_mode = ctx
             .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .take(&UNTIL_COMMA_DISCARD)
-            .parse_opt();
+            .parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::trace!("Gsa::new: selection_mode={:?}", op_mode);
-        let nav_mode = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let nav_mode = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::trace!("Gsa::new: mode={:?}", nav_mode);
 
         let mut svid = Vec::with_capacity(12);
         for _ in 0..12 {
-            match ctx.take(&UNTIL_COMMA_DISCARD).parse_opt::<u8>() {
+            match ctx.parse_field::<_, u8>(&UNTIL_COMMA_DISCARD)? {
                 Some(sat_id) => svid.push(sat_id),
                 None => continue,
             }
         }
         clerk::trace!("Gsa::new: satellite_ids={:?}", svid);
 
-        let pdop = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let pdop = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::trace!("Gsa::new: pdop={:?}", pdop);
 
-        let hdop = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let hdop = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         clerk::trace!("Gsa::new: hdop={:?}", hdop);
 
-        let vdop = ctx.take(&UNTIL_COMMA_OR_STAR_DISCARD).parse_opt::<f64>();
+        let vdop = ctx.parse_field::<_, f64>(&UNTIL_COMMA_OR_STAR_DISCARD)?;
         clerk::trace!("Gsa::new: vdop={:?}", vdop);
 
-        let system_id = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let system_id = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
         clerk::trace!("Gsa::new: system_id={:?}", system_id);
 
         Ok(Gsa {
@@ -156,6 +155,7 @@ mod test {
 
     use clerk::{LogLevel, init_log_with_level};
     use float_cmp::assert_approx_eq;
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
 
@@ -195,4 +195,16 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GNGSA,A,3,05,07,1x,14,15,17,19,23,24,,,,1.0,0.7,0.7,1*73";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Gsa::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 17);
+        assert_eq!(err.rule(), "parse::<u8>");
+    }
 }
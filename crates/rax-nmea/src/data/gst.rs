@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 
 use crate::data::{INmeaData, Talker};
 use crate::macros::readonly_struct;
@@ -47,14 +47,16 @@ impl INmeaData for Gst {
     fn new(ctx: &mut StrParserContext, talker: Talker) -> miette::Result<Self> {
         ctx.global(&NMEA_VALIDATE)?;
 
-        let time = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_TIME);
-        let rms = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let std_major = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let std_minor = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let orient = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let std_lat = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let std_lon = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let std_alt = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let time = ctx
+            .skip_strict(&UNTIL_COMMA_DISCARD)?
+            .take_field(&NMEA_TIME)?;
+        let rms = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let std_major = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let std_minor = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let orient = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let std_lat = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let std_lon = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let std_alt = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
 
         Ok(Gst {
             talker,
@@ -108,6 +110,7 @@ impl fmt::Debug for Gst {
 mod test {
 
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -129,4 +132,16 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGST,182141.000,15.5,15.3,7.2,21.x,0.9,0.5,0.8*14";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Gst::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 32);
+        assert_eq!(err.rule(), "parse::<f64>");
+    }
 }
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rax::str_parser::{IStrGlobalRule, ParseOptExt, StrParserContext};
+use rax::str_parser::{IStrGlobalRule, StrParserContext, parse_rule_name};
 use serde::{Deserialize, Serialize};
 
 use crate::data::{INmeaData, Talker};
@@ -18,6 +18,13 @@ pub struct Satellite {
     az: Option<u16>,
     /// Signal-to-noise ratio.
     cno: Option<u8>,
+    /// Zero-based index of the GSV line the satellite was reported in.
+    #[serde(default)]
+    fragment: usize,
+}
+impl Satellite {
+    /// Zero-based index of the GSV line the satellite was reported in.
+    pub fn fragment(&self) -> usize { self.fragment }
 }
 
 impl fmt::Debug for Satellite {
@@ -35,6 +42,7 @@ impl fmt::Debug for Satellite {
         if let Some(snr) = self.cno {
             ds.field("snr", &snr);
         }
+        ds.field("fragment", &self.fragment);
         ds.finish()
     }
 }
@@ -60,54 +68,33 @@ impl INmeaData for Gsv {
             NMEA_VALIDATE.apply(l)?;
         }
 
-        // Count the number of lines and satellites
-        let line_count = ctx.full_str().lines().count();
-        clerk::trace!("Gsv::new: line_count={line_count}");
-
-        // The first line contains the talker, number of lines, and number of satellites
-        let satellite_count = ctx
-            .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .take(&UNTIL_COMMA_DISCARD)
-            .parse_opt::<usize>()
-            .expect("Cannot get the count of satellites.");
-        clerk::trace!("Gsv::new: satellite_count={satellite_count}");
-
-        // The last line may have fewer than 4 satellites, so we calculate how many
-        // satellites are in the last line based on the total count.
-        let last_line_satellite_count = {
-            let rem = satellite_count % 4;
-            if rem == 0 && line_count == 1 && satellite_count != 0 {
-                4
-            } else {
-                rem
-            }
-        };
-        clerk::trace!("Gsv::new: last_line_satellite_count={last_line_satellite_count}");
-
-        let mut satellites = Vec::with_capacity(satellite_count);
-        // Parse all but the last line (each has 4 satellites)
-        for _ in 0..line_count - 1 {
-            for _ in 0..3 {
-                satellites.push(Self::parse_satellite(ctx, false)?);
+        let mut satellite_count = 0;
+        let mut satellites = Vec::new();
+        let mut signal_id = None;
+        for mut line in ctx.lines() {
+            let fragment = line.position().line;
+            // Each line starts with the header, number of lines and line number.
+            line.skip_strict(&UNTIL_COMMA_DISCARD)?
+                .skip_strict(&UNTIL_COMMA_DISCARD)?
+                .skip_strict(&UNTIL_COMMA_DISCARD)?;
+            let offset = line.offset();
+            let count = line
+                .parse_field::<_, usize>(&UNTIL_COMMA_DISCARD)?
+                .ok_or_else(|| line.error_at(offset, &parse_rule_name::<usize>()))?;
+            if fragment == 0 {
+                satellite_count = count;
+                clerk::trace!("Gsv::new: satellite_count={satellite_count}");
+                satellites.reserve(satellite_count);
             }
-            satellites.push(Self::parse_satellite(ctx, true)?);
-            // Skip any extra fields after the 4th satellite in the line
-            ctx.skip(&UNTIL_COMMA_DISCARD)
-                .skip(&UNTIL_COMMA_DISCARD)
-                .skip(&UNTIL_COMMA_DISCARD)
-                .skip(&UNTIL_COMMA_DISCARD);
-        }
 
-        // Parse the last line (may have fewer than 4 satellites)
-        if last_line_satellite_count != 0 {
-            for _ in 0..(last_line_satellite_count - 1) {
-                satellites.push(Self::parse_satellite(ctx, false)?);
+            // Every line holds up to 4 satellites, the last one may hold fewer.
+            let in_line = satellite_count.saturating_sub(satellites.len()).min(4);
+            clerk::trace!("Gsv::new: fragment={fragment}, satellites={in_line}");
+            for _ in 0..in_line {
+                satellites.push(Self::parse_satellite(&mut line, fragment)?);
             }
-            satellites.push(Self::parse_satellite(ctx, true)?);
+            signal_id = line.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
         }
-        let signal_id = ctx.take(&UNTIL_COMMA_OR_STAR_DISCARD).parse_opt();
 
         Ok(Self {
             talker,
@@ -117,22 +104,18 @@ impl INmeaData for Gsv {
     }
 }
 impl Gsv {
-    /// Helper to parse a single satellite entry.
-    /// If `last` is true, the SNR field is terminated by a star.
-    fn parse_satellite(ctx: &mut StrParserContext, last: bool) -> miette::Result<Satellite> {
-        let id = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let elevation_degrees = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let azimuth_degree = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let snr = if last {
-            ctx.take(&UNTIL_STAR_DISCARD).parse_opt()
-        } else {
-            ctx.take(&UNTIL_COMMA_DISCARD).parse_opt()
-        };
+    /// Helper to parse a single satellite entry from line `fragment`.
+    fn parse_satellite(ctx: &mut StrParserContext, fragment: usize) -> miette::Result<Satellite> {
+        let id = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let elevation_degrees = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let azimuth_degree = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let snr = ctx.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
         Ok(Satellite {
             svid: id,
             elv: elevation_degrees,
             az: azimuth_degree,
             cno: snr,
+            fragment,
         })
     }
 }
@@ -149,6 +132,7 @@ impl fmt::Debug for Gsv {
 #[cfg(test)]
 mod test {
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
 
@@ -269,4 +253,55 @@ mod test {
         assert_eq!(gsv.satellites.len(), 0);
         Ok(())
     }
+
+    #[test]
+    fn test_gsv_fragments() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGSV,3,1,10,25,68,053,47,21,59,306,49,29,56,161,49,31,36,265,49*79\r\n$GPGSV,3,2,10,12,29,048,49,05,22,123,49,18,13,000,49,01,00,000,49*72\r\n$GPGSV,3,3,10,14,00,000,03,16,00,000,27*7C";
+        let mut ctx = StrParserContext::new();
+        let gsv = Gsv::new(ctx.init(s.to_string()), Talker::GP)?;
+        let fragments: Vec<usize> = gsv.satellites.iter().map(Satellite::fragment).collect();
+        assert_eq!(fragments, [0, 0, 0, 0, 1, 1, 1, 1, 2, 2]);
+        Ok(())
+    }
+    #[test]
+    fn test_gsv_error_fragment() {
+        init_log_with_level(LogLevel::TRACE);
+        let s =
+            "$GPGSV,2,1,05,25,68,053,47,21,59,306,49,29,56,161,49,31,36,265,49*7C\r\n$GPGSV,2,2*55";
+        let mut ctx = StrParserContext::new();
+        let report = Gsv::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.line(), 1);
+    }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGSV,1,1,4,02,35,291,,03,09,12x,,05,14,305,,06,38,226,*0F";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Gsv::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 30);
+        assert_eq!(err.rule(), "parse::<u16>");
+    }
+
+    #[test]
+    fn test_gsv_count_error() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPGSV,2,1,05,25,68,053,47,21,59,306,49,29,56,161,49,31,36,265,49*7C\r\n$GPGSV,2,2,x5,*18";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Gsv::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.line(), 1);
+        assert_eq!(err.offset(), 11);
+        assert_eq!(err.rule(), "parse::<usize>");
+
+        let report = Gsv::new(ctx.init("$GPGSV,1,1,,*55".to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 11);
+        assert_eq!(err.rule(), "parse::<usize>");
+    }
 }
@@ -1,7 +1,7 @@
 use std::fmt;
 
 use chrono::NaiveDate;
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 
 use crate::data::{INmeaData, PosMode, Status, Talker};
 use crate::macros::readonly_struct;
@@ -54,15 +54,17 @@ impl INmeaData for Rmc {
     fn new(ctx: &mut StrParserContext, talker: Talker) -> miette::Result<Self> {
         ctx.global(&NMEA_VALIDATE)?;
 
-        let time = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_TIME);
-        let status = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let lat = ctx.take(&NMEA_COORD);
-        let lon = ctx.take(&NMEA_COORD);
-        let spd = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let cog = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let date = ctx.take(&NMEA_DATE);
-        let mv = ctx.take(&NMEA_DEGREE);
-        let pos_mode = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let time = ctx
+            .skip_strict(&UNTIL_COMMA_DISCARD)?
+            .take_field(&NMEA_TIME)?;
+        let status = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let lat = ctx.take_field(&NMEA_COORD)?;
+        let lon = ctx.take_field(&NMEA_COORD)?;
+        let spd = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let cog = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let date = ctx.take_field(&NMEA_DATE)?;
+        let mv = ctx.take_field(&NMEA_DEGREE)?;
+        let pos_mode = ctx.parse_field(&UNTIL_COMMA_OR_STAR_DISCARD)?;
         Ok(Rmc {
             talker,
             time,
@@ -119,6 +121,7 @@ impl fmt::Debug for Rmc {
 mod test {
     use clerk::{LogLevel, init_log_with_level};
     use float_cmp::assert_approx_eq;
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -159,4 +162,27 @@ mod test {
         assert_eq!(rmc.pos_mode, Some(PosMode::NotValid));
         Ok(())
     }
+
+    #[test]
+    fn test_new_rmc_nav_status() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        // NMEA 4.1 appends a navigational status after the mode.
+        let s = "$GPRMC,,V,,,,,,,,,,N,V*29";
+        let mut ctx = StrParserContext::new();
+        let rmc = Rmc::new(ctx.init(s.to_string()), Talker::GP)?;
+        assert_eq!(rmc.pos_mode, Some(PosMode::NotValid));
+        Ok(())
+    }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPRMC,110125,A,5505.337580,N,03858.653666,E,148.8,84.6,31x317,8.9,E,D*66";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Rmc::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 56);
+        assert_eq!(err.rule(), "NmeaDate");
+    }
 }
@@ -1,36 +1,16 @@
 use std::fmt;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
-
-use crate::data::{INmeaData, PosMode, Talker};
-use crate::macros::readonly_struct;
-use crate::rules::*;
-
-readonly_struct!(
-    Ths ,
-    "Poll a standard message (Talker ID GL)",
-    {talker: Talker},
-
-    {
-        headt: Option<f64>,
-        "Heading of vehicle (true)"
-    },
-    {
-        mi: Option<PosMode>,
-        "Mode indicator"
-    }
-);
-impl INmeaData for Ths {
-    fn new(ctx: &mut StrParserContext, talker: Talker) -> miette::Result<Self> {
-        ctx.global(&NMEA_VALIDATE)?;
-        let headt = ctx
-            .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .take(&UNTIL_COMMA_DISCARD)
-            .parse_opt();
-        let mi = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
-
-        Ok(Ths { talker, headt, mi })
-    }
+use crate::NmeaSentence;
+use crate::data::{PosMode, Talker};
+
+/// Poll a standard message (Talker ID GL)
+#[derive(Clone, NmeaSentence)]
+pub struct Ths {
+    talker: Talker,
+    /// Heading of vehicle (true)
+    headt: Option<f64>,
+    /// Mode indicator
+    mi: Option<PosMode>,
 }
 
 impl fmt::Debug for Ths {
@@ -53,8 +33,10 @@ impl fmt::Debug for Ths {
 mod test {
 
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::StrParserContext;
 
     use super::*;
+    use crate::data::INmeaData;
     #[test]
     fn test_parse() -> miette::Result<()> {
         init_log_with_level(LogLevel::TRACE);
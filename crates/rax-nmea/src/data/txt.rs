@@ -1,6 +1,6 @@
 use std::fmt::{self};
 
-use rax::str_parser::{IStrGlobalRule, ParseOptExt, StrParserContext};
+use rax::str_parser::{IStrGlobalRule, StrParserContext};
 use serde::{Deserialize, Serialize};
 
 use crate::data::{INmeaData, Talker};
@@ -43,7 +43,7 @@ readonly_struct!(
 
     {
         message : Vec<( Option<TxtType>,Option<String>)>,
-        "Text information"
+        "Text information, one entry per sentence fragment in line order"
     }
 );
 
@@ -56,18 +56,16 @@ impl INmeaData for Txt {
         }
 
         let mut infos = Vec::new();
-        for _ in 0..ctx.full_str().lines().count() {
-            let txt_type = ctx
+        for mut line in ctx.lines() {
+            let txt_type = line
                 .skip_strict(&UNTIL_COMMA_DISCARD)?
                 .skip_strict(&UNTIL_COMMA_DISCARD)?
                 .skip_strict(&UNTIL_COMMA_DISCARD)?
-                .take(&UNTIL_COMMA_DISCARD)
-                .parse_opt::<u8>()
+                .parse_field::<_, u8>(&UNTIL_COMMA_DISCARD)?
                 .map(TxtType::try_from)
                 .and_then(Result::ok);
-            let info = ctx.take(&UNTIL_STAR_DISCARD).map(|f| f.to_string());
+            let info = line.take(&UNTIL_STAR_DISCARD).map(|f| f.to_string());
             infos.push((txt_type, info));
-            ctx.skip(&UNTIL_NEW_LINE_DISCARD);
         }
 
         Ok(Self {
@@ -104,6 +102,7 @@ impl fmt::Debug for Txt {
 #[cfg(test)]
 mod test {
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -123,4 +122,16 @@ mod test {
         assert_eq!(txt.message[2].1, Some("SW=URANUS2,V2.2.1.0".to_string()));
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPTXT,03,01,x2,MA=CASIC*6D";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Txt::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 13);
+        assert_eq!(err.rule(), "parse::<u8>");
+    }
 }
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 
 use crate::data::{INmeaData, Talker};
 use crate::macros::readonly_struct;
@@ -33,14 +33,13 @@ impl INmeaData for Vlw {
         ctx.global(&NMEA_VALIDATE)?;
         let twd = ctx
             .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .take(&UNTIL_COMMA_DISCARD)
-            .parse_opt();
+            .parse_field(&UNTIL_COMMA_DISCARD)?;
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
-        let wd = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let wd = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
-        let tgd = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let tgd = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
-        let gd = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let gd = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         Ok(Vlw {
             talker,
             twd,
@@ -77,6 +76,7 @@ impl fmt::Debug for Vlw {
 mod test {
 
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -90,4 +90,16 @@ mod test {
         assert_eq!(vlw.gd.unwrap(), 1.2);
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPVLW,,N,,N,15.x,N,1.2,N*25";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Vlw::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 13);
+        assert_eq!(err.rule(), "parse::<f64>");
+    }
 }
@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 
 use crate::data::{INmeaData, PosMode, Talker};
 use crate::macros::readonly_struct;
@@ -37,20 +37,19 @@ impl INmeaData for Vtg {
 
         let cogt = ctx
             .skip_strict(&UNTIL_COMMA_DISCARD)?
-            .take(&UNTIL_COMMA_DISCARD)
-            .parse_opt();
+            .parse_field(&UNTIL_COMMA_DISCARD)?;
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
 
-        let cogm = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let cogm = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
 
-        let sogn = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let sogn = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
 
-        let sogk = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
+        let sogk = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
         ctx.skip_strict(&UNTIL_COMMA_DISCARD)?;
 
-        let pos_mode = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let pos_mode = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
 
         Ok(Vtg {
             talker,
@@ -92,6 +91,7 @@ impl fmt::Debug for Vtg {
 mod test {
     use clerk::{LogLevel, init_log_with_level};
     use float_cmp::assert_approx_eq;
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -109,4 +109,16 @@ mod test {
         assert_eq!(vtg.pos_mode.unwrap(), PosMode::Differential);
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPVTG,83.7,T,83.7,M,14x.3,N,271.0,K,D*6C";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Vtg::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 21);
+        assert_eq!(err.rule(), "parse::<f64>");
+    }
 }
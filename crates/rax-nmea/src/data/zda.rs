@@ -1,6 +1,6 @@
 use std::fmt;
 
-use rax::str_parser::{ParseOptExt, StrParserContext};
+use rax::str_parser::StrParserContext;
 
 use crate::data::{INmeaData, Talker};
 use crate::macros::readonly_struct;
@@ -40,12 +40,14 @@ impl INmeaData for Zda {
     fn new(ctx: &mut StrParserContext, talker: Talker) -> miette::Result<Self> {
         ctx.global(&NMEA_VALIDATE)?;
 
-        let time = ctx.skip_strict(&UNTIL_COMMA_DISCARD)?.take(&NMEA_TIME);
-        let day = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let month = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let year = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let ltzh = ctx.take(&UNTIL_COMMA_DISCARD).parse_opt();
-        let ltzn = ctx.take(&UNTIL_STAR_DISCARD).parse_opt();
+        let time = ctx
+            .skip_strict(&UNTIL_COMMA_DISCARD)?
+            .take_field(&NMEA_TIME)?;
+        let day = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let month = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let year = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let ltzh = ctx.parse_field(&UNTIL_COMMA_DISCARD)?;
+        let ltzn = ctx.parse_field(&UNTIL_STAR_DISCARD)?;
 
         Ok(Zda {
             talker,
@@ -90,6 +92,7 @@ impl fmt::Debug for Zda {
 #[cfg(test)]
 mod test {
     use clerk::{LogLevel, init_log_with_level};
+    use rax::str_parser::{NumMode, StrParserError};
 
     use super::*;
     #[test]
@@ -107,4 +110,16 @@ mod test {
         assert_eq!(zda.ltzn.unwrap(), 0);
         Ok(())
     }
+
+    #[test]
+    fn test_malformed_field() {
+        init_log_with_level(LogLevel::TRACE);
+        let s = "$GPZDA,160012.71,11,03,20x4,-1,00*35";
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        let report = Zda::new(ctx.init(s.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 23);
+        assert_eq!(err.rule(), "parse::<u16>");
+    }
 }
@@ -1,12 +1,18 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
+use rax::io::SourceId;
+
 use crate::data::{Identifier, Talker};
 
 /// Dispatcher reads and groups sentences, handling both single and multi-line
 /// messages.
+///
+/// Multi-line buffers are kept per source, so sentences from several
+/// receivers merged by a `MuxReader` can be fed through
+/// [`dispatch_from`](Self::dispatch_from) without mixing their fragments.
 pub struct Dispatcher {
-    buffer: HashMap<(Talker, Identifier), String>, // (total count, accumulated sentence)
+    buffer: HashMap<(SourceId, Talker, Identifier), String>, // accumulated sentence
 }
 
 impl Default for Dispatcher {
@@ -44,6 +50,7 @@ impl Dispatcher {
     /// Handle multi-line sentences (e.g., GSV, TXT).
     fn process_multilines(
         &mut self,
+        source: SourceId,
         talker: Talker,
         identifier: Identifier,
         sentence: String,
@@ -62,12 +69,12 @@ impl Dispatcher {
         match (
             idx == 1,
             count == idx,
-            self.buffer.get(&(talker, identifier)),
+            self.buffer.get(&(source, talker, identifier)),
         ) {
             (true, true, _) => Some((talker, identifier, sentence)),
             // First line of multi-line, buffer it
             (true, false, None) => {
-                self.buffer.insert((talker, identifier), sentence);
+                self.buffer.insert((source, talker, identifier), sentence);
                 None
             }
             // Newer first line arrived, replace old buffer
@@ -78,14 +85,14 @@ impl Dispatcher {
                     identifier,
                     _old
                 );
-                self.buffer.insert((talker, identifier), sentence);
+                self.buffer.insert((source, talker, identifier), sentence);
                 None
             }
             // Last line, combine with buffer and return
             (false, true, Some(v)) => {
                 clerk::debug!("`{}{}` is complete.", talker, identifier);
                 let combined = format!("{v}{sentence}");
-                self.buffer.remove(&(talker, identifier));
+                self.buffer.remove(&(source, talker, identifier));
                 Some((talker, identifier, combined))
             }
             // Out-of-order line, skip
@@ -106,7 +113,7 @@ impl Dispatcher {
                     identifier,
                     sentence
                 );
-                if let Some(entry) = self.buffer.get_mut(&(talker, identifier)) {
+                if let Some(entry) = self.buffer.get_mut(&(source, talker, identifier)) {
                     entry.push_str(&sentence);
                 }
                 None
@@ -114,8 +121,24 @@ impl Dispatcher {
         }
     }
 
+    /// Drop the half-received multi-line sentences of `source`, e.g. after
+    /// it reconnected.
+    pub fn reset_source(&mut self, source: SourceId) {
+        self.buffer.retain(|(s, _, _), _| *s != source);
+    }
+
     /// Dispatches sentences, handling both single and multi-line types.
     pub fn dispatch(&mut self, sentence: String) -> Option<(Talker, Identifier, String)> {
+        self.dispatch_from(0, sentence)
+    }
+
+    /// Like [`dispatch`](Self::dispatch), but buffers multi-line sentences
+    /// separately for each `source`.
+    pub fn dispatch_from(
+        &mut self,
+        source: SourceId,
+        sentence: String,
+    ) -> Option<(Talker, Identifier, String)> {
         if let Some((talker, identifier, sentence)) = self.preprocess(sentence) {
             match identifier {
                 // Single-line sentences
@@ -140,7 +163,7 @@ impl Dispatcher {
 
                 // Multi-line sentences
                 Identifier::GSV | Identifier::TXT => {
-                    self.process_multilines(talker, identifier, sentence)
+                    self.process_multilines(source, talker, identifier, sentence)
                 }
             }
         } else {
@@ -179,4 +202,41 @@ mod test {
 
         Ok(())
     }
+
+    #[test]
+    fn test_dispatch_from_keeps_sources_apart() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let file = File::open("data/nmea_with_sat_info.log").into_diagnostic()?;
+        let mut reader = RaxReader::new(io::BufReader::new(file));
+        let mut gsv = Vec::new();
+        while let Some(line) = reader.read_line()? {
+            if line.starts_with("$GPGSV,3,") {
+                gsv.push(line);
+            }
+        }
+        assert!(gsv.len() >= 3);
+
+        // Interleave the same multi-line GSV from two sources.
+        let mut dispatcher = Dispatcher::new();
+        let mut complete = Vec::new();
+        for line in &gsv[..3] {
+            for source in [0, 1] {
+                if let Some((.., s)) = dispatcher.dispatch_from(source, line.clone()) {
+                    complete.push(s);
+                }
+            }
+        }
+        let expected: String = gsv[..3].iter().map(|l| l.as_str()).collect();
+        assert_eq!(complete, [expected.clone(), expected]);
+
+        // Resetting one source drops only its buffer.
+        dispatcher.dispatch_from(0, gsv[0].clone());
+        dispatcher.dispatch_from(1, gsv[0].clone());
+        dispatcher.reset_source(0);
+        dispatcher.dispatch_from(0, gsv[1].clone());
+        dispatcher.dispatch_from(1, gsv[1].clone());
+        assert!(dispatcher.dispatch_from(0, gsv[2].clone()).is_none());
+        assert!(dispatcher.dispatch_from(1, gsv[2].clone()).is_some());
+        Ok(())
+    }
 }
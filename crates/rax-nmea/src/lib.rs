@@ -1,5 +1,22 @@
+// Lets the `NmeaSentence` derive refer to `::rax_nmea` from inside this crate.
+extern crate self as rax_nmea;
+
 pub mod data;
 mod dispatcher;
 mod macros;
+mod message;
 pub mod rules;
 pub use dispatcher::*;
+pub use message::*;
+#[cfg(feature = "stream")]
+mod stream;
+pub use rax_nmea_derive::NmeaSentence;
+#[cfg(feature = "stream")]
+pub use stream::*;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use miette;
+    pub use rax;
+    pub use serde;
+}
@@ -0,0 +1,144 @@
+use chrono::NaiveTime;
+use rax::str_parser::StrParserContext;
+use serde::{Deserialize, Serialize};
+
+use crate::data::*;
+
+/// Expands to `$msg.time()` for sentences marked `[time]`, to `None` for the
+/// others.
+macro_rules! message_time {
+    ($msg:ident, time) => {
+        *$msg.time()
+    };
+    ($msg:ident,) => {
+        None
+    };
+}
+
+/// Defines [`NmeaMessage`] from `Identifier => Type` pairs. A sentence type
+/// added here is parsed, serialized and accessed like all others.
+macro_rules! nmea_messages {
+    ($($identifier:ident => $data:ident $([$time:ident])?),* $(,)?) => {
+        /// Any parsed sentence.
+        ///
+        /// Serialized with the identifier as tag next to the sentence's
+        /// fields, e.g. `{"identifier": "GGA", "talker": "GN", ...}`.
+        #[derive(Debug, Clone, Serialize, Deserialize)]
+        #[serde(tag = "identifier", rename_all = "UPPERCASE")]
+        pub enum NmeaMessage {
+            $($data($data),)*
+        }
+
+        impl NmeaMessage {
+            /// Parse `sentence` as the type `identifier` names, reusing
+            /// `ctx`.
+            pub fn parse_with(
+                ctx: &mut StrParserContext,
+                talker: Talker,
+                identifier: Identifier,
+                sentence: String,
+            ) -> miette::Result<Self> {
+                let ctx = ctx.init(sentence);
+                let msg = match identifier {
+                    $(Identifier::$identifier => Self::$data($data::new(ctx, talker)?),)*
+                };
+                Ok(msg)
+            }
+
+            pub fn identifier(&self) -> Identifier {
+                match self {
+                    $(Self::$data(_) => Identifier::$identifier,)*
+                }
+            }
+
+            pub fn talker(&self) -> Talker {
+                match self {
+                    $(Self::$data(msg) => *msg.talker(),)*
+                }
+            }
+
+            /// UTC time of the sentence, for types that carry one.
+            pub fn time(&self) -> Option<NaiveTime> {
+                match self {
+                    $(Self::$data(_msg) => message_time!(_msg, $($time)?),)*
+                }
+            }
+        }
+
+        $(
+            impl From<$data> for NmeaMessage {
+                fn from(msg: $data) -> Self { Self::$data(msg) }
+            }
+        )*
+    };
+}
+
+nmea_messages! {
+    DHV => Dhv [time],
+    DTM => Dtm,
+    GBQ => Gbq,
+    GBS => Gbs [time],
+    GGA => Gga [time],
+    GLL => Gll [time],
+    GLQ => Glq,
+    GNQ => Gnq,
+    GNS => Gns [time],
+    GPQ => Gpq,
+    GRS => Grs [time],
+    GSA => Gsa,
+    GST => Gst [time],
+    GSV => Gsv,
+    RMC => Rmc [time],
+    THS => Ths,
+    TXT => Txt,
+    VLW => Vlw,
+    VTG => Vtg,
+    ZDA => Zda [time],
+}
+
+impl NmeaMessage {
+    /// Parse a sentence as returned by
+    /// [`Dispatcher::dispatch`](crate::Dispatcher::dispatch).
+    pub fn parse(talker: Talker, identifier: Identifier, sentence: String) -> miette::Result<Self> {
+        Self::parse_with(&mut StrParserContext::new(), talker, identifier, sentence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+
+    const GGA: &str = "$GPGGA,110256,5505.676996,N,03856.028884,E,2,08,0.7,2135.0,M,14.0,M,,*7D";
+    const GSA: &str = "$GNGSA,A,3,05,07,13,14,15,17,19,23,24,,,,1.0,0.7,0.7,1*38";
+
+    #[test]
+    fn test_parse() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let msg = NmeaMessage::parse(Talker::GP, Identifier::GGA, GGA.to_string())?;
+        assert!(matches!(msg, NmeaMessage::Gga(_)));
+        assert_eq!(msg.identifier(), Identifier::GGA);
+        assert_eq!(msg.talker(), Talker::GP);
+        assert_eq!(msg.time(), NaiveTime::from_hms_opt(11, 2, 56));
+
+        let msg = NmeaMessage::parse(Talker::GN, Identifier::GSA, GSA.to_string())?;
+        assert_eq!(msg.identifier(), Identifier::GSA);
+        assert_eq!(msg.time(), None);
+
+        assert!(NmeaMessage::parse(Talker::GP, Identifier::GSV, GGA.to_string()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_serde_tag() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let msg = NmeaMessage::parse(Talker::GP, Identifier::GGA, GGA.to_string())?;
+        let text = toml::to_string(&msg).unwrap();
+        assert!(text.starts_with("identifier = \"GGA\"\ntalker = \"GP\"\n"));
+        let back: NmeaMessage = toml::from_str(&text).unwrap();
+        assert_eq!(back.identifier(), Identifier::GGA);
+        assert_eq!(back.time(), msg.time());
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use futures_util::{Stream, StreamExt};
+
+use crate::Dispatcher;
+use crate::data::{Identifier, Talker};
+
+/// Dispatch a stream of lines, e.g. from
+/// [`AsyncRaxReader::into_stream`](rax::io::AsyncRaxReader::into_stream),
+/// into complete sentences. Multi-line sentences are yielded once their
+/// last line arrived; read errors are passed through.
+pub fn dispatch_stream<S>(
+    lines: S,
+) -> impl Stream<Item = miette::Result<(Talker, Identifier, String)>>
+where
+    S: Stream<Item = miette::Result<String>>,
+{
+    let mut dispatcher = Dispatcher::new();
+    lines.filter_map(move |line| {
+        let item = match line {
+            Ok(line) => dispatcher.dispatch(line).map(Ok),
+            Err(e) => Some(Err(e)),
+        };
+        std::future::ready(item)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use clerk::{LogLevel, init_log_with_level};
+    use futures_util::TryStreamExt;
+    use miette::IntoDiagnostic;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_dispatch_stream() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let log = fs::read_to_string("data/nmea_with_sat_info.log").into_diagnostic()?;
+        let lines =
+            futures_util::stream::iter(log.split_inclusive('\n').map(|l| Ok(l.to_string())));
+        let sentences: Vec<_> = dispatch_stream(lines).try_collect().await?;
+        let gsv = sentences
+            .iter()
+            .find(|(talker, id, _)| *talker == Talker::GP && *id == Identifier::GSV)
+            .unwrap();
+        // The three GSV lines are merged into one item.
+        assert_eq!(gsv.2.matches("$GPGSV").count(), 3);
+        assert!(sentences.len() < log.lines().count());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dispatch_stream_passes_errors() {
+        init_log_with_level(LogLevel::TRACE);
+        let lines = futures_util::stream::iter([
+            Err(miette::miette!("boom")),
+            Ok("$GPGGA,1*4B\r\n".to_string()),
+        ]);
+        let items: Vec<_> = dispatch_stream(lines).collect().await;
+        assert_eq!(items.len(), 2);
+        assert!(items[0].is_err());
+    }
+}
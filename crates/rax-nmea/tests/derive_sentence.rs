@@ -0,0 +1,88 @@
+use chrono::{NaiveDate, NaiveTime};
+use clerk::{LogLevel, init_log_with_level};
+use float_cmp::assert_approx_eq;
+use rax::str_parser::{NumMode, StrParserContext, StrParserError};
+use rax_nmea::NmeaSentence;
+use rax_nmea::data::{INmeaData, PosMode, Talker};
+use rax_nmea::rules::NMEA_DATE;
+
+/// A vendor position sentence.
+#[derive(Clone, Debug, NmeaSentence)]
+pub struct Pvt {
+    talker: Talker,
+    /// Time of fix
+    #[nmea(time)]
+    time: Option<NaiveTime>,
+    #[nmea(coord)]
+    lat: Option<f64>,
+    #[nmea(coord)]
+    lon: Option<f64>,
+    /// Satellites used, after a reserved field
+    #[nmea(skip = 1)]
+    num_sv: Option<u8>,
+    #[nmea(rule = NMEA_DATE)]
+    date: Option<NaiveDate>,
+    mode: Option<PosMode>,
+}
+
+const SENTENCE: &str = "$PXPVT,123519.00,4807.038,N,01131.000,E,ignored,08,230325,A*5C";
+
+#[test]
+fn test_derive_parse() -> miette::Result<()> {
+    init_log_with_level(LogLevel::TRACE);
+    let mut ctx = StrParserContext::new();
+    let pvt = Pvt::new(ctx.init(SENTENCE.to_string()), Talker::GP)?;
+    assert_eq!(pvt.talker(), &Talker::GP);
+    assert_eq!(pvt.time().unwrap().to_string(), "12:35:19");
+    assert_approx_eq!(f64, pvt.lat().unwrap(), 48.1173);
+    assert_approx_eq!(f64, pvt.lon().unwrap(), 11.516666666666667);
+    assert_eq!(pvt.num_sv(), &Some(8));
+    assert_eq!(pvt.date(), &NaiveDate::from_ymd_opt(2025, 3, 23));
+    assert_eq!(pvt.mode(), &Some(PosMode::Autonomous));
+    Ok(())
+}
+
+#[test]
+fn test_derive_checksum_error() {
+    init_log_with_level(LogLevel::TRACE);
+    let mut ctx = StrParserContext::new();
+    let broken = SENTENCE.replace("*5C", "*00");
+    assert!(Pvt::new(ctx.init(broken), Talker::GP).is_err());
+}
+
+#[test]
+fn test_derive_serde() -> miette::Result<()> {
+    init_log_with_level(LogLevel::TRACE);
+    let mut ctx = StrParserContext::new();
+    let pvt = Pvt::new(ctx.init(SENTENCE.to_string()), Talker::GP)?;
+    let text = toml::to_string(&pvt).unwrap();
+    assert!(text.contains("num_sv = 8"));
+    let back: Pvt = toml::from_str(&text).unwrap();
+    assert_eq!(back.time(), pvt.time());
+    assert_eq!(back.mode(), pvt.mode());
+    Ok(())
+}
+
+#[test]
+fn test_derive_malformed_field() {
+    init_log_with_level(LogLevel::TRACE);
+    let mut ctx = StrParserContext::new();
+    ctx.field_mode(NumMode::Strict);
+    for (sentence, offset, rule) in [
+        (
+            "$PXPVT,123519.00,4807.038,N,01131.000,E,ignored,x8,230325,A*14",
+            48,
+            "parse::<u8>",
+        ),
+        (
+            "$PXPVT,123519.00,4807.038,N,01131.000,E,ignored,08,23x325,A*14",
+            51,
+            "NmeaDate",
+        ),
+    ] {
+        let report = Pvt::new(ctx.init(sentence.to_string()), Talker::GP).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), offset);
+        assert_eq!(err.rule(), rule);
+    }
+}
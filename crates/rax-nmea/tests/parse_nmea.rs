@@ -5,8 +5,7 @@ use clerk::{LogLevel, init_log_with_level};
 use miette::IntoDiagnostic;
 use rax::io::{IRaxReader, RaxReader};
 use rax::str_parser::StrParserContext;
-use rax_nmea::Dispatcher;
-use rax_nmea::data::*;
+use rax_nmea::{Dispatcher, NmeaMessage};
 #[test]
 fn test_parse_nmea() -> miette::Result<()> {
     init_log_with_level(LogLevel::WARN);
@@ -22,90 +21,8 @@ fn test_parse_nmea() -> miette::Result<()> {
             .read_line()?
             .and_then(|line| dispatcher.dispatch(line))
         {
-            match identifier {
-                Identifier::DHV => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Dhv::new(ctx, talker)?;
-                }
-                Identifier::GBS => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gbs::new(ctx, talker)?;
-                }
-                Identifier::GGA => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gga::new(ctx, talker)?;
-                }
-                Identifier::GLL => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gll::new(ctx, talker)?;
-                }
-                Identifier::GNS => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gns::new(ctx, talker)?;
-                }
-                Identifier::GRS => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Grs::new(ctx, talker)?;
-                }
-                Identifier::GSA => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gsa::new(ctx, talker)?;
-                }
-                Identifier::GST => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gst::new(ctx, talker)?;
-                }
-                Identifier::GSV => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gsv::new(ctx, talker)?;
-                }
-                Identifier::RMC => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Rmc::new(ctx, talker)?;
-                }
-                Identifier::TXT => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Txt::new(ctx, talker)?;
-                }
-                Identifier::VTG => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Vtg::new(ctx, talker)?;
-                }
-                Identifier::ZDA => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Zda::new(ctx, talker)?;
-                }
-                Identifier::DTM => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Dtm::new(ctx, talker)?;
-                }
-                Identifier::GBQ => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gbq::new(ctx, talker)?;
-                }
-                Identifier::GLQ => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Glq::new(ctx, talker)?;
-                }
-                Identifier::GNQ => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gnq::new(ctx, talker)?;
-                }
-                Identifier::GPQ => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Gpq::new(ctx, talker)?;
-                }
-                Identifier::THS => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Ths::new(ctx, talker)?;
-                }
-                Identifier::VLW => {
-                    let ctx = ctx.init(sentence);
-                    let _ = Vlw::new(ctx, talker)?;
-                }
-            }
+            let _ = NmeaMessage::parse_with(&mut ctx, talker, identifier, sentence)?;
         }
     }
-
     Ok(())
 }
@@ -1,11 +1,22 @@
 //! Device enumeration and filtering utilities for serial devices.
 //!
 //! This module provides types and functions to list and filter serial devices
-//! (such as USB, PCI, and Bluetooth devices) using the `serialport` crate.
+//! (such as USB, PCI, and Bluetooth devices) using the `serialport` crate,
+//! and to watch for devices being plugged in or removed.
 
 use miette::IntoDiagnostic;
+use serde::{Deserialize, Serialize};
 use serialport::{SerialPortInfo, SerialPortType};
-#[derive(Debug, Clone, PartialEq, Eq)]
+
+mod filter;
+pub use filter::*;
+mod probe;
+pub use probe::*;
+mod watch;
+pub use watch::*;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum DeviceType {
     Usb,
     Pci,
@@ -84,10 +95,13 @@ where
         "[Device] {} serial ports after filtering",
         filtered_ports.len()
     );
-    // Map filtered SerialPortInfo into DeviceInfo, logging each port type
-    Ok(filtered_ports
-        .into_iter()
-        .map(|p| match p.port_type {
+    Ok(filtered_ports.into_iter().map(DeviceInfo::from).collect())
+}
+
+impl From<SerialPortInfo> for DeviceInfo {
+    /// Convert a `serialport` port description, logging its type.
+    fn from(p: SerialPortInfo) -> Self {
+        match p.port_type {
             SerialPortType::UsbPort(info) => {
                 // Log USB port details
                 clerk::debug!("[Device] USB port: {:?}", p.port_name);
@@ -132,8 +146,8 @@ where
                     None,
                 )
             }
-        })
-        .collect())
+        }
+    }
 }
 
 /// Utility struct for common device filters.
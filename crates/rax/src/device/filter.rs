@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use serialport::{SerialPortInfo, SerialPortType};
+
+use super::{DeviceInfo, DeviceType};
+
+/// Declarative device filter, e.g. loaded from a config file.
+///
+/// String criteria are glob patterns where `*` matches any run of characters
+/// and `?` a single character; matching is case-sensitive. A criterion on a
+/// field the device does not report, such as the vendor ID of a PCI port,
+/// does not match.
+///
+/// In TOML, "the u-blox receiver with serial number 12345" reads:
+///
+/// ```toml
+/// [device]
+/// all = [{ vendor_id = 0x1546 }, { serial_number = "12345" }]
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceFilterExpr {
+    /// Port name, e.g. `/dev/ttyACM*` or `COM?`.
+    Name(String),
+    DeviceType(DeviceType),
+    VendorId(u16),
+    ProductId(u16),
+    Manufacturer(String),
+    Product(String),
+    SerialNumber(String),
+    /// Matches if every expression matches; an empty list matches all
+    /// devices.
+    All(Vec<DeviceFilterExpr>),
+    /// Matches if any expression matches.
+    Any(Vec<DeviceFilterExpr>),
+    Not(Box<DeviceFilterExpr>),
+}
+
+/// Borrowed view of the fields a filter looks at.
+struct Fields<'a> {
+    name: &'a str,
+    device_type: DeviceType,
+    vendor_id: Option<u16>,
+    product_id: Option<u16>,
+    manufacturer: Option<&'a str>,
+    product: Option<&'a str>,
+    serial_number: Option<&'a str>,
+}
+
+impl DeviceFilterExpr {
+    /// Whether the port matches, usable as the filter of
+    /// [`list_devices`](super::list_devices) and
+    /// [`DeviceWatcher`](super::DeviceWatcher).
+    pub fn matches(&self, port: &SerialPortInfo) -> bool {
+        let mut fields = Fields {
+            name: &port.port_name,
+            device_type: DeviceType::Unknown,
+            vendor_id: None,
+            product_id: None,
+            manufacturer: None,
+            product: None,
+            serial_number: None,
+        };
+        match &port.port_type {
+            SerialPortType::UsbPort(usb) => {
+                fields.device_type = DeviceType::Usb;
+                fields.vendor_id = Some(usb.vid);
+                fields.product_id = Some(usb.pid);
+                fields.manufacturer = usb.manufacturer.as_deref();
+                fields.product = usb.product.as_deref();
+                fields.serial_number = usb.serial_number.as_deref();
+            }
+            SerialPortType::PciPort => fields.device_type = DeviceType::Pci,
+            SerialPortType::BluetoothPort => fields.device_type = DeviceType::Bluetooth,
+            SerialPortType::Unknown => {}
+        }
+        self.eval(&fields)
+    }
+
+    /// Whether an already listed device matches.
+    pub fn matches_info(&self, info: &DeviceInfo) -> bool {
+        self.eval(&Fields {
+            name: info.name(),
+            device_type: info.device_type().clone(),
+            vendor_id: info.vendor_id(),
+            product_id: info.product_id(),
+            manufacturer: info.manufacturer(),
+            product: info.product(),
+            serial_number: info.serial_number(),
+        })
+    }
+
+    fn eval(&self, f: &Fields) -> bool {
+        let pattern = |pattern: &str, value: Option<&str>| value.is_some_and(|v| glob(pattern, v));
+        match self {
+            Self::Name(p) => glob(p, f.name),
+            Self::DeviceType(t) => *t == f.device_type,
+            Self::VendorId(id) => f.vendor_id == Some(*id),
+            Self::ProductId(id) => f.product_id == Some(*id),
+            Self::Manufacturer(p) => pattern(p, f.manufacturer),
+            Self::Product(p) => pattern(p, f.product),
+            Self::SerialNumber(p) => pattern(p, f.serial_number),
+            Self::All(exprs) => exprs.iter().all(|e| e.eval(f)),
+            Self::Any(exprs) => exprs.iter().any(|e| e.eval(f)),
+            Self::Not(expr) => !expr.eval(f),
+        }
+    }
+}
+
+/// Match `text` against a glob `pattern` with `*` and `?` wildcards.
+fn glob(pattern: &str, text: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let t: Vec<char> = text.chars().collect();
+    let (mut pi, mut ti) = (0, 0);
+    // Position of the last `*` and the text index it currently covers up to.
+    let mut star: Option<(usize, usize)> = None;
+    while ti < t.len() {
+        match p.get(pi) {
+            Some('*') => {
+                star = Some((pi, ti));
+                pi += 1;
+            }
+            Some(&c) if c == '?' || c == t[ti] => {
+                pi += 1;
+                ti += 1;
+            }
+            _ => match star {
+                Some((sp, st)) => {
+                    pi = sp + 1;
+                    ti = st + 1;
+                    star = Some((sp, st + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    p[pi..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+    use serialport::UsbPortInfo;
+
+    use super::*;
+
+    fn ublox(name: &str, serial: &str) -> SerialPortInfo {
+        SerialPortInfo {
+            port_name: name.to_string(),
+            port_type: SerialPortType::UsbPort(UsbPortInfo {
+                vid: 0x1546,
+                pid: 0x01a8,
+                serial_number: Some(serial.to_string()),
+                manufacturer: Some("u-blox AG - www.u-blox.com".to_string()),
+                product: Some("u-blox GNSS receiver".to_string()),
+            }),
+        }
+    }
+
+    fn pci(name: &str) -> SerialPortInfo {
+        SerialPortInfo {
+            port_name: name.to_string(),
+            port_type: SerialPortType::PciPort,
+        }
+    }
+
+    #[test]
+    fn test_glob() {
+        init_log_with_level(LogLevel::TRACE);
+        assert!(glob("/dev/ttyACM*", "/dev/ttyACM0"));
+        assert!(glob("COM?", "COM5"));
+        assert!(!glob("COM?", "COM15"));
+        assert!(glob("*u-blox*", "u-blox AG"));
+        assert!(glob("a*b*c", "axxbyybc"));
+        assert!(!glob("a*b", "ab c"));
+        assert!(glob("*", ""));
+        assert!(!glob("", "x"));
+    }
+
+    #[test]
+    fn test_matches() {
+        init_log_with_level(LogLevel::TRACE);
+        let expr = DeviceFilterExpr::All(vec![
+            DeviceFilterExpr::VendorId(0x1546),
+            DeviceFilterExpr::Manufacturer("u-blox*".to_string()),
+            DeviceFilterExpr::Not(Box::new(DeviceFilterExpr::SerialNumber("BAD*".to_string()))),
+        ]);
+        assert!(expr.matches(&ublox("/dev/ttyACM0", "A1")));
+        assert!(!expr.matches(&ublox("/dev/ttyACM0", "BAD1")));
+        assert!(!expr.matches(&pci("/dev/ttyS0")));
+
+        let expr = DeviceFilterExpr::Any(vec![
+            DeviceFilterExpr::DeviceType(DeviceType::Pci),
+            DeviceFilterExpr::Name("/dev/ttyUSB*".to_string()),
+        ]);
+        assert!(expr.matches(&pci("/dev/ttyS0")));
+        assert!(!expr.matches(&ublox("/dev/ttyACM0", "A1")));
+        assert!(DeviceFilterExpr::All(vec![]).matches(&pci("COM1")));
+        assert!(!DeviceFilterExpr::Any(vec![]).matches(&pci("COM1")));
+    }
+
+    #[test]
+    fn test_matches_info() {
+        init_log_with_level(LogLevel::TRACE);
+        let expr = DeviceFilterExpr::SerialNumber("A?".to_string());
+        let port = ublox("/dev/ttyACM0", "A1");
+        assert!(expr.matches(&port));
+        assert!(expr.matches_info(&DeviceInfo::from(port)));
+    }
+
+    #[test]
+    fn test_from_toml() {
+        init_log_with_level(LogLevel::TRACE);
+        #[derive(Deserialize)]
+        struct Config {
+            device: DeviceFilterExpr,
+        }
+        let config: Config = toml::from_str(
+            r#"
+            [device]
+            all = [
+                { vendor_id = 0x1546 },
+                { device_type = "usb" },
+                { not = { name = "COM1" } },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.device,
+            DeviceFilterExpr::All(vec![
+                DeviceFilterExpr::VendorId(0x1546),
+                DeviceFilterExpr::DeviceType(DeviceType::Usb),
+                DeviceFilterExpr::Not(Box::new(DeviceFilterExpr::Name("COM1".to_string()))),
+            ])
+        );
+    }
+}
@@ -0,0 +1,383 @@
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use miette::IntoDiagnostic;
+
+use crate::io::checksum;
+use crate::str_parser::IStrGlobalRule;
+
+/// Baud rates tried by [`BaudProbe::new`], most common first.
+pub const COMMON_BAUD_RATES: [u32; 8] = [9600, 4800, 38400, 115200, 19200, 57600, 230400, 460800];
+
+/// Protocol family recognised in probed data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Protocol {
+    /// `$` sentences accepted by the validator.
+    Nmea,
+    /// `!` sentences with a valid checksum.
+    Ais,
+    /// u-blox binary frames with a valid checksum.
+    Ubx,
+    /// RTCM 3 frames with a valid CRC.
+    Rtcm3,
+}
+
+/// What was read at one baud rate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateScore {
+    pub baud_rate: u32,
+    pub bytes: usize,
+    /// Lines starting with `$` or `!`.
+    pub lines: usize,
+    /// Lines accepted by the validator.
+    pub valid: usize,
+    pub protocols: Vec<Protocol>,
+}
+
+impl RateScore {
+    /// Score `data` read at `baud_rate`, counting the lines `validator`
+    /// accepts, e.g. `rax_nmea::rules::NMEA_VALIDATE`.
+    pub fn from_bytes<V>(baud_rate: u32, data: &[u8], validator: &V) -> Self
+    where
+        V: for<'a> IStrGlobalRule<'a, Output = miette::Result<()>>,
+    {
+        let mut score = Self {
+            baud_rate,
+            bytes: data.len(),
+            lines: 0,
+            valid: 0,
+            protocols: Vec::new(),
+        };
+        for raw in data.split(|&b| b == b'\n') {
+            // Sentences may follow binary frames on the same "line".
+            let Some(start) = raw.iter().position(|&b| b == b'$' || b == b'!') else {
+                continue;
+            };
+            let Ok(line) = std::str::from_utf8(&raw[start..]) else {
+                continue;
+            };
+            score.lines += 1;
+            if validator.apply(line).is_ok() {
+                score.valid += 1;
+                score.found(if line.starts_with('!') {
+                    Protocol::Ais
+                } else {
+                    Protocol::Nmea
+                });
+            } else if line.starts_with('!') && has_valid_checksum(line) {
+                score.found(Protocol::Ais);
+            }
+        }
+        if contains_ubx(data) {
+            score.found(Protocol::Ubx);
+        }
+        if contains_rtcm3(data) {
+            score.found(Protocol::Rtcm3);
+        }
+        score.protocols.sort();
+        score
+    }
+
+    fn found(&mut self, protocol: Protocol) {
+        if !self.protocols.contains(&protocol) {
+            self.protocols.push(protocol);
+        }
+    }
+}
+
+/// Scores of every probed rate, in probing order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeResult {
+    pub scores: Vec<RateScore>,
+}
+
+impl ProbeResult {
+    /// The rate with the most valid lines, or `None` if no rate yielded a
+    /// valid line or a recognised binary frame. The number of recognised
+    /// protocols breaks ties.
+    pub fn best(&self) -> Option<&RateScore> {
+        self.scores
+            .iter()
+            .filter(|s| s.valid > 0 || !s.protocols.is_empty())
+            .max_by_key(|s| (s.valid, s.protocols.len()))
+    }
+}
+
+/// Finds the baud rate of a serial device by listening at candidate rates.
+#[derive(Debug, Clone)]
+pub struct BaudProbe {
+    rates: Vec<u32>,
+    window: Duration,
+}
+
+impl Default for BaudProbe {
+    fn default() -> Self { Self::new() }
+}
+
+impl BaudProbe {
+    /// Probe [`COMMON_BAUD_RATES`] for 1.5 s each.
+    pub fn new() -> Self {
+        Self {
+            rates: COMMON_BAUD_RATES.to_vec(),
+            window: Duration::from_millis(1500),
+        }
+    }
+    /// Candidate rates, tried in order.
+    pub fn rates(mut self, rates: &[u32]) -> Self {
+        self.rates = rates.to_vec();
+        self
+    }
+    /// How long to listen at each rate. Most receivers send once per second,
+    /// so the window should be longer than that.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Listen on `path` at every candidate rate and score what was received.
+    /// Stops early once a rate yields at least three valid lines. A rate the
+    /// port can not be opened at is logged and scored zero; the probe only
+    /// fails if no rate could be opened.
+    pub fn probe<V>(&self, path: &str, validator: &V) -> miette::Result<ProbeResult>
+    where
+        V: for<'a> IStrGlobalRule<'a, Output = miette::Result<()>>,
+    {
+        let mut scores = Vec::with_capacity(self.rates.len());
+        let mut failed = 0;
+        for &baud_rate in &self.rates {
+            let data = match self.listen(path, baud_rate) {
+                Ok(data) => data,
+                Err(e) => {
+                    clerk::warn!("[BaudProbe] {} @ {}: {}", path, baud_rate, e);
+                    failed += 1;
+                    if failed == self.rates.len() {
+                        return Err(e.wrap_err(format!("could not open {path} at any baud rate")));
+                    }
+                    Vec::new()
+                }
+            };
+            let score = RateScore::from_bytes(baud_rate, &data, validator);
+            clerk::info!(
+                "[BaudProbe] {} @ {}: {} bytes, {}/{} valid lines, {:?}",
+                path,
+                baud_rate,
+                score.bytes,
+                score.valid,
+                score.lines,
+                score.protocols
+            );
+            let done = score.valid >= 3;
+            scores.push(score);
+            if done {
+                break;
+            }
+        }
+        Ok(ProbeResult { scores })
+    }
+
+    fn listen(&self, path: &str, baud_rate: u32) -> miette::Result<Vec<u8>> {
+        let mut port = serialport::new(path, baud_rate)
+            .timeout(Duration::from_millis(50))
+            .open()
+            .into_diagnostic()?;
+        // Drop bytes received at the previous rate.
+        let _ = port.clear(serialport::ClearBuffer::Input);
+        let deadline = Instant::now() + self.window;
+        let mut data = Vec::new();
+        let mut buf = [0u8; 512];
+        while Instant::now() < deadline {
+            match port.read(&mut buf) {
+                Ok(n) => data.extend_from_slice(&buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => {}
+                Err(e) => return Err(e).into_diagnostic(),
+            }
+        }
+        Ok(data)
+    }
+}
+
+fn has_valid_checksum(line: &str) -> bool {
+    let line = line.trim_end();
+    let Some((body, hex)) = line.rsplit_once('*') else {
+        return false;
+    };
+    u8::from_str_radix(hex, 16).is_ok_and(|c| hex.len() == 2 && c == checksum(body))
+}
+
+/// Whether `data` holds a complete UBX frame with a valid checksum.
+fn contains_ubx(data: &[u8]) -> bool {
+    memchr::memmem::find_iter(data, &[0xB5, 0x62]).any(|i| {
+        let frame = &data[i + 2..];
+        if frame.len() < 4 {
+            return false;
+        }
+        let len = u16::from_le_bytes([frame[2], frame[3]]) as usize;
+        let Some(body) = frame.get(..4 + len) else {
+            return false;
+        };
+        let Some(ck) = frame.get(4 + len..6 + len) else {
+            return false;
+        };
+        let (a, b) = body.iter().fold((0u8, 0u8), |(a, b), &x| {
+            let a = a.wrapping_add(x);
+            (a, b.wrapping_add(a))
+        });
+        ck == [a, b]
+    })
+}
+
+/// Whether `data` holds a complete RTCM 3 frame with a valid CRC.
+fn contains_rtcm3(data: &[u8]) -> bool {
+    memchr::memchr_iter(0xD3, data).any(|i| {
+        let frame = &data[i..];
+        if frame.len() < 3 || frame[1] & 0xFC != 0 {
+            return false;
+        }
+        let len = (((frame[1] & 0x03) as usize) << 8) | frame[2] as usize;
+        match frame.get(..3 + len + 3) {
+            Some(frame) => {
+                let (body, crc) = frame.split_at(3 + len);
+                crc24q(body).to_be_bytes()[1..] == *crc
+            }
+            None => false,
+        }
+    })
+}
+
+fn crc24q(data: &[u8]) -> u32 {
+    data.iter().fold(0u32, |mut crc, &b| {
+        crc ^= (b as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x0100_0000 != 0 {
+                crc ^= 0x0186_4CFB;
+            }
+        }
+        crc & 0x00FF_FFFF
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::IRule;
+
+    /// Accepts `$` sentences with a valid checksum, like `NmeaValidate`.
+    struct Checksum;
+    impl IRule for Checksum {
+        fn name(&self) -> &str { "Checksum" }
+    }
+    impl<'a> IStrGlobalRule<'a> for Checksum {
+        type Output = miette::Result<()>;
+        fn apply(&self, input: &'a str) -> miette::Result<()> {
+            if input.starts_with('$') && has_valid_checksum(input) {
+                Ok(())
+            } else {
+                miette::bail!("invalid sentence")
+            }
+        }
+    }
+
+    fn ubx_frame() -> Vec<u8> {
+        let body = [0x01, 0x07, 0x02, 0x00, 0xAA, 0xBB];
+        let (a, b) = body.iter().fold((0u8, 0u8), |(a, b), &x| {
+            let a = a.wrapping_add(x);
+            (a, b.wrapping_add(a))
+        });
+        [&[0xB5, 0x62][..], &body, &[a, b]].concat()
+    }
+
+    fn rtcm_frame() -> Vec<u8> {
+        let mut frame = vec![0xD3, 0x00, 0x03, 0x3E, 0xD0, 0x00];
+        let crc = crc24q(&frame).to_be_bytes();
+        frame.extend_from_slice(&crc[1..]);
+        frame
+    }
+
+    #[test]
+    fn test_crc24q() {
+        init_log_with_level(LogLevel::TRACE);
+        assert_eq!(crc24q(b"123456789"), 0x00CD_E703);
+    }
+
+    #[test]
+    fn test_score_nmea_and_ais() {
+        init_log_with_level(LogLevel::TRACE);
+        let data = b"$GPGGA,1*4B\r\n$GPGGA,1*00\r\n!AIVDM,1*4A\r\n\xff\xfe\r\n";
+        let score = RateScore::from_bytes(9600, data, &Checksum);
+        assert_eq!((score.lines, score.valid), (3, 1));
+        assert_eq!(score.protocols, [Protocol::Nmea, Protocol::Ais]);
+    }
+
+    #[test]
+    fn test_score_binary() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut data = ubx_frame();
+        data.extend(rtcm_frame());
+        data.extend_from_slice(b"$GPGGA,1*4B\r\n");
+        let score = RateScore::from_bytes(115200, &data, &Checksum);
+        assert_eq!(score.valid, 1);
+        assert_eq!(
+            score.protocols,
+            [Protocol::Nmea, Protocol::Ubx, Protocol::Rtcm3]
+        );
+        let garbage = [0xB5, 0x62, 0x01, 0x07, 0x02, 0x00, 0xAA, 0xBB, 0, 0];
+        assert!(
+            RateScore::from_bytes(9600, &garbage, &Checksum)
+                .protocols
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_best() {
+        init_log_with_level(LogLevel::TRACE);
+        let garbage = RateScore::from_bytes(4800, b"\x13$\x88*\n", &Checksum);
+        let good = RateScore::from_bytes(9600, b"$GPGGA,1*4B\n$GPGGA,1*4B\n", &Checksum);
+        let result = ProbeResult {
+            scores: vec![garbage.clone(), good],
+        };
+        assert_eq!(result.best().map(|s| s.baud_rate), Some(9600));
+        let result = ProbeResult {
+            scores: vec![garbage],
+        };
+        assert_eq!(result.best(), None);
+    }
+
+    #[test]
+    fn test_probe_fails_if_no_rate_opens() {
+        init_log_with_level(LogLevel::TRACE);
+        let err = BaudProbe::new()
+            .rates(&[9600, 4800])
+            .window(Duration::from_millis(10))
+            .probe("/dev/rax-no-such-device", &Checksum)
+            .unwrap_err();
+        assert!(err.to_string().contains("at any baud rate"));
+    }
+
+    #[cfg(all(feature = "pty", unix))]
+    #[test]
+    fn test_probe_virtual_device() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let device = std::sync::Arc::new(crate::pty::VirtualSerial::open()?);
+        let sender = {
+            let device = device.clone();
+            std::thread::spawn(move || {
+                for _ in 0..20 {
+                    let _ = device.send_sentence("$GPGGA,1");
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+            })
+        };
+        let result = BaudProbe::new()
+            .rates(&[9600, 115200])
+            .window(Duration::from_millis(100))
+            .probe(device.path(), &Checksum)?;
+        sender.join().unwrap();
+        assert_eq!(result.best().map(|s| s.baud_rate), Some(9600));
+        assert_eq!(result.scores.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use miette::IntoDiagnostic;
+use serialport::SerialPortInfo;
+
+use super::DeviceInfo;
+
+/// A change reported by [`DeviceWatcher`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceEvent {
+    Added(DeviceInfo),
+    Removed(DeviceInfo),
+}
+
+type Scanner = Box<dyn FnMut() -> miette::Result<Vec<SerialPortInfo>> + Send>;
+
+/// Watches for serial devices being plugged in or removed.
+///
+/// The port list is polled every [`interval`](Self::interval) and compared
+/// to the previous scan. Devices present at the first scan are reported as
+/// `Added`, so a consumer attaches to receivers that are already connected
+/// the same way as to new ones. A device whose description changed is
+/// reported as removed and added again.
+pub struct DeviceWatcher<F: Fn(&SerialPortInfo) -> bool> {
+    filter: F,
+    scanner: Scanner,
+    interval: Duration,
+    known: Vec<DeviceInfo>,
+    pending: VecDeque<DeviceEvent>,
+}
+
+impl<F: Fn(&SerialPortInfo) -> bool> DeviceWatcher<F> {
+    /// Watch the system's serial ports that match `filter`.
+    pub fn new(filter: F) -> Self {
+        Self::with_scanner(filter, || serialport::available_ports().into_diagnostic())
+    }
+    /// Watch the ports returned by `scanner` instead of the system's, e.g.
+    /// to test code that reacts to hotplug events.
+    pub fn with_scanner(
+        filter: F,
+        scanner: impl FnMut() -> miette::Result<Vec<SerialPortInfo>> + Send + 'static,
+    ) -> Self {
+        Self {
+            filter,
+            scanner: Box::new(scanner),
+            interval: Duration::from_secs(1),
+            known: Vec::new(),
+            pending: VecDeque::new(),
+        }
+    }
+    /// Time between two scans. Defaults to one second.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+    /// Devices seen at the last scan.
+    pub fn devices(&self) -> &[DeviceInfo] { &self.known }
+
+    /// Scan once and return the changes since the previous scan, without
+    /// waiting.
+    pub fn poll(&mut self) -> miette::Result<Vec<DeviceEvent>> {
+        let current: Vec<DeviceInfo> = (self.scanner)()?
+            .into_iter()
+            .filter(&self.filter)
+            .map(DeviceInfo::from)
+            .collect();
+        let mut events: Vec<DeviceEvent> = self
+            .known
+            .iter()
+            .filter(|d| !current.contains(d))
+            .map(|d| DeviceEvent::Removed(d.clone()))
+            .collect();
+        events.extend(
+            current
+                .iter()
+                .filter(|d| !self.known.contains(d))
+                .map(|d| DeviceEvent::Added(d.clone())),
+        );
+        for event in &events {
+            clerk::info!("[DeviceWatcher] {:?}", event);
+        }
+        self.known = current;
+        Ok(events)
+    }
+
+    /// Block until the next device is added or removed.
+    pub fn next_event(&mut self) -> miette::Result<DeviceEvent> {
+        loop {
+            if let Some(event) = self.next_pending()? {
+                return Ok(event);
+            }
+            std::thread::sleep(self.interval);
+        }
+    }
+
+    /// Async variant of [`next_event`](Self::next_event). Scans are short
+    /// blocking calls; only the wait between them is async.
+    #[cfg(feature = "async")]
+    pub async fn next_event_async(&mut self) -> miette::Result<DeviceEvent> {
+        loop {
+            if let Some(event) = self.next_pending()? {
+                return Ok(event);
+            }
+            tokio::time::sleep(self.interval).await;
+        }
+    }
+
+    /// Consume the watcher and return its events as a stream that never
+    /// ends, see [`next_event_async`](Self::next_event_async).
+    #[cfg(feature = "stream")]
+    pub fn into_stream(self) -> impl futures_util::Stream<Item = miette::Result<DeviceEvent>> {
+        futures_util::stream::unfold(self, |mut watcher| async move {
+            let event = watcher.next_event_async().await;
+            Some((event, watcher))
+        })
+    }
+
+    /// Pop a queued event, scanning once if the queue is empty.
+    fn next_pending(&mut self) -> miette::Result<Option<DeviceEvent>> {
+        if self.pending.is_empty() {
+            let events = self.poll()?;
+            self.pending.extend(events);
+        }
+        Ok(self.pending.pop_front())
+    }
+}
+
+impl<F: Fn(&SerialPortInfo) -> bool> Iterator for DeviceWatcher<F> {
+    type Item = miette::Result<DeviceEvent>;
+
+    /// Blocks until the next event; the iterator never ends.
+    fn next(&mut self) -> Option<Self::Item> { Some(self.next_event()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use clerk::{LogLevel, init_log_with_level};
+    #[cfg(feature = "stream")]
+    use futures_util::StreamExt;
+    use serialport::SerialPortType;
+
+    use super::*;
+    use crate::device::{DeviceFilter, DeviceType};
+
+    fn port(name: &str, port_type: SerialPortType) -> SerialPortInfo {
+        SerialPortInfo {
+            port_name: name.to_string(),
+            port_type,
+        }
+    }
+
+    fn usb(name: &str) -> SerialPortInfo {
+        port(
+            name,
+            SerialPortType::UsbPort(serialport::UsbPortInfo {
+                vid: 0x1546,
+                pid: 0x01a8,
+                serial_number: None,
+                manufacturer: None,
+                product: None,
+            }),
+        )
+    }
+
+    /// A watcher over a port list the test can change.
+    fn scripted<F: Fn(&SerialPortInfo) -> bool>(
+        filter: F,
+    ) -> (DeviceWatcher<F>, Arc<Mutex<Vec<SerialPortInfo>>>) {
+        let ports = Arc::new(Mutex::new(Vec::new()));
+        let scanner = {
+            let ports = ports.clone();
+            move || Ok(ports.lock().unwrap().clone())
+        };
+        let watcher = DeviceWatcher::with_scanner(filter, scanner).interval(Duration::ZERO);
+        (watcher, ports)
+    }
+
+    #[test]
+    fn test_added_and_removed() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let (mut watcher, ports) = scripted(DeviceFilter::all);
+        ports.lock().unwrap().push(usb("/dev/ttyACM0"));
+        let events = watcher.poll()?;
+        assert_eq!(events.len(), 1);
+        assert!(matches!(&events[0], DeviceEvent::Added(d) if d.name() == "/dev/ttyACM0"));
+        assert!(watcher.poll()?.is_empty());
+
+        *ports.lock().unwrap() = vec![usb("/dev/ttyACM1")];
+        let events = watcher.poll()?;
+        assert!(matches!(&events[0], DeviceEvent::Removed(d) if d.name() == "/dev/ttyACM0"));
+        assert!(matches!(&events[1], DeviceEvent::Added(d) if d.name() == "/dev/ttyACM1"));
+        assert_eq!(watcher.devices().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_and_iterator() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let (watcher, ports) = scripted(DeviceFilter::usb);
+        *ports.lock().unwrap() = vec![
+            port("/dev/ttyS0", SerialPortType::PciPort),
+            usb("/dev/ttyACM0"),
+        ];
+        let event = watcher.take(1).next().unwrap()?;
+        match event {
+            DeviceEvent::Added(d) => assert_eq!(d.device_type(), &DeviceType::Usb),
+            other => panic!("unexpected {other:?}"),
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_next_event_async() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let (mut watcher, ports) = scripted(DeviceFilter::all);
+        let plug = {
+            let ports = ports.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                ports.lock().unwrap().push(usb("/dev/ttyACM0"));
+            })
+        };
+        let event = watcher.next_event_async().await?;
+        assert!(matches!(event, DeviceEvent::Added(_)));
+        plug.await.into_diagnostic()?;
+        Ok(())
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_into_stream() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let (watcher, ports) = scripted(DeviceFilter::all);
+        ports.lock().unwrap().push(usb("/dev/ttyACM0"));
+        let mut events = std::pin::pin!(watcher.into_stream());
+        let added = events.next().await.unwrap()?;
+        assert!(matches!(&added, DeviceEvent::Added(d) if d.name() == "/dev/ttyACM0"));
+        ports.lock().unwrap().clear();
+        let removed = events.next().await.unwrap()?;
+        assert!(matches!(removed, DeviceEvent::Removed(_)));
+        Ok(())
+    }
+}
@@ -1,7 +1,47 @@
+mod capture;
+pub use capture::*;
+mod framing;
+pub use framing::*;
 mod reader;
 pub use reader::*;
+mod reader_mux;
+pub use reader_mux::*;
+mod reconnect;
+pub use reconnect::*;
+mod replay;
+pub use replay::*;
+mod writer;
+pub use writer::*;
 
 #[cfg(feature = "async")]
 mod reader_async;
 #[cfg(feature = "async")]
 pub use reader_async::*;
+#[cfg(feature = "async")]
+mod reader_bridge;
+#[cfg(feature = "async")]
+pub use reader_bridge::*;
+#[cfg(feature = "async")]
+mod reader_mux_async;
+#[cfg(feature = "async")]
+pub use reader_mux_async::*;
+#[cfg(feature = "async")]
+mod reader_resilient;
+#[cfg(feature = "async")]
+pub use reader_resilient::*;
+#[cfg(feature = "async")]
+mod writer_async;
+#[cfg(feature = "async")]
+pub use writer_async::*;
+#[cfg(feature = "net")]
+mod net;
+#[cfg(feature = "net")]
+pub use net::*;
+#[cfg(feature = "stream")]
+mod codec;
+#[cfg(feature = "stream")]
+pub use codec::*;
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "stream")]
+pub use stream::*;
@@ -0,0 +1,222 @@
+use std::io::Write;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use miette::IntoDiagnostic;
+
+use super::{IRaxLendingReader, IRaxReader};
+
+/// First line of every capture file.
+pub const CAPTURE_HEADER: &str = "#rax-capture v1";
+
+/// One raw line of a capture file together with the time it was received.
+///
+/// On disk a record is `<offset_ns>\t<wall_ns>\t<line>`, where `offset_ns`
+/// is the monotonic time since the capture started, `wall_ns` the host clock
+/// in nanoseconds since the Unix epoch, and `line` the raw line with `\\`,
+/// `\r`, `\n` and `\t` escaped.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CaptureRecord {
+    pub offset: Duration,
+    pub wall: SystemTime,
+    pub line: String,
+}
+
+impl CaptureRecord {
+    /// Encode the record as one line of a capture file, without the
+    /// trailing newline.
+    pub fn encode(&self) -> String {
+        let wall = self
+            .wall
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let mut out = format!("{}\t{}\t", self.offset.as_nanos(), wall);
+        for c in self.line.chars() {
+            match c {
+                '\\' => out.push_str("\\\\"),
+                '\r' => out.push_str("\\r"),
+                '\n' => out.push_str("\\n"),
+                '\t' => out.push_str("\\t"),
+                c => out.push(c),
+            }
+        }
+        out
+    }
+
+    /// Parse one line of a capture file, with or without its newline.
+    pub fn decode(record: &str) -> miette::Result<Self> {
+        let record = record.trim_end_matches(['\r', '\n']);
+        let mut parts = record.splitn(3, '\t');
+        let (Some(offset), Some(wall), Some(escaped)) = (parts.next(), parts.next(), parts.next())
+        else {
+            miette::bail!("malformed capture record: {record:?}");
+        };
+        let offset: u64 = offset.parse().into_diagnostic()?;
+        let wall: u64 = wall.parse().into_diagnostic()?;
+        let mut line = String::with_capacity(escaped.len());
+        let mut chars = escaped.chars();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                line.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('\\') => line.push('\\'),
+                Some('r') => line.push('\r'),
+                Some('n') => line.push('\n'),
+                Some('t') => line.push('\t'),
+                other => miette::bail!("invalid escape `\\{other:?}` in capture record"),
+            }
+        }
+        Ok(Self {
+            offset: Duration::from_nanos(offset),
+            wall: UNIX_EPOCH + Duration::from_nanos(wall),
+            line,
+        })
+    }
+}
+
+/// Wraps a reader and writes every line it returns to a capture sink.
+///
+/// Lines are passed through unchanged. The capture starts when the
+/// `TeeReader` is created; the header is written at the same time.
+pub struct TeeReader<R: IRaxReader, W: Write> {
+    inner: R,
+    capture: W,
+    started: Instant,
+}
+
+impl<R: IRaxReader, W: Write> TeeReader<R, W> {
+    pub fn new(inner: R, mut capture: W) -> miette::Result<Self> {
+        writeln!(capture, "{CAPTURE_HEADER}").into_diagnostic()?;
+        Ok(Self {
+            inner,
+            capture,
+            started: Instant::now(),
+        })
+    }
+    /// Flush the capture sink and return the reader and the sink.
+    pub fn into_parts(mut self) -> miette::Result<(R, W)> {
+        self.capture.flush().into_diagnostic()?;
+        Ok((self.inner, self.capture))
+    }
+}
+
+/// Write `line` to the capture, stamped with the time it was returned.
+fn record(capture: &mut impl Write, started: Instant, line: &str) -> miette::Result<()> {
+    let record = CaptureRecord {
+        offset: started.elapsed(),
+        wall: SystemTime::now(),
+        line: line.to_string(),
+    };
+    writeln!(capture, "{}", record.encode()).into_diagnostic()
+}
+
+impl<R: IRaxReader, W: Write> IRaxReader for TeeReader<R, W> {
+    fn read_line(&mut self) -> miette::Result<Option<String>> {
+        let line = self.inner.read_line()?;
+        if let Some(line) = &line {
+            record(&mut self.capture, self.started, line)?;
+        }
+        Ok(line)
+    }
+
+    /// Reads line by line, so every line is stamped when it arrives.
+    fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>> {
+        let mut lines = Vec::with_capacity(count);
+        while lines.len() < count {
+            match self.read_line()? {
+                Some(line) => lines.push(line),
+                None => break,
+            }
+        }
+        Ok(lines)
+    }
+}
+
+impl<R: IRaxLendingReader, W: Write> IRaxLendingReader for TeeReader<R, W> {
+    fn read_line_ref(&mut self) -> miette::Result<Option<&str>> {
+        let Some(line) = self.inner.read_line_ref()? else {
+            return Ok(None);
+        };
+        record(&mut self.capture, self.started, line)?;
+        Ok(Some(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::io::{RaxReader, ReplayReader};
+
+    #[test]
+    fn test_record_roundtrip() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let record = CaptureRecord {
+            offset: Duration::from_millis(1500),
+            wall: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+            line: "$GP\\x\t,1*00\r\n".to_string(),
+        };
+        let encoded = record.encode();
+        assert_eq!(
+            encoded,
+            "1500000000\t1700000000000000000\t$GP\\\\x\\t,1*00\\r\\n"
+        );
+        assert_eq!(CaptureRecord::decode(&encoded)?, record);
+        assert!(CaptureRecord::decode("12\tnope").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tee_reader() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let inner = RaxReader::new(Cursor::new("a\r\nb\nc\n"));
+        let mut tee = TeeReader::new(inner, Vec::new())?;
+        assert_eq!(tee.read_line()?.as_deref(), Some("a\r\n"));
+        assert_eq!(tee.read_line_ref()?, Some("b\n"));
+        assert_eq!(tee.read_lines_by_count(5)?, ["c\n"]);
+        assert_eq!(tee.read_line()?, None);
+        let (_, capture) = tee.into_parts()?;
+        let capture = String::from_utf8(capture).unwrap();
+        let mut lines = capture.lines();
+        assert_eq!(lines.next(), Some(CAPTURE_HEADER));
+        let records = lines
+            .map(CaptureRecord::decode)
+            .collect::<miette::Result<Vec<_>>>()?;
+        let raw: Vec<_> = records.iter().map(|r| r.line.as_str()).collect();
+        assert_eq!(raw, ["a\r\n", "b\n", "c\n"]);
+        assert!(records.windows(2).all(|w| w[0].offset <= w[1].offset));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tee_reader_stamps_each_line() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut text = format!("{CAPTURE_HEADER}\n");
+        for (ms, line) in [(0, "a\n"), (40, "b\n")] {
+            let record = CaptureRecord {
+                offset: Duration::from_millis(ms),
+                wall: UNIX_EPOCH,
+                line: line.to_string(),
+            };
+            text.push_str(&record.encode());
+            text.push('\n');
+        }
+        let inner = ReplayReader::from_reader(Cursor::new(text))?;
+        let mut tee = TeeReader::new(inner, Vec::new())?;
+        assert_eq!(tee.read_lines_by_count(2)?, ["a\n", "b\n"]);
+        let (_, capture) = tee.into_parts()?;
+        let capture = String::from_utf8(capture).unwrap();
+        let records = capture
+            .lines()
+            .skip(1)
+            .map(CaptureRecord::decode)
+            .collect::<miette::Result<Vec<_>>>()?;
+        assert!(records[1].offset - records[0].offset >= Duration::from_millis(35));
+        Ok(())
+    }
+}
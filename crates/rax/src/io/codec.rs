@@ -0,0 +1,143 @@
+use std::io;
+
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use super::{FrameEvent, Framer, Framing, ReaderStats, frame_sentence};
+
+/// `tokio_util` codec for NMEA style line protocols.
+///
+/// Decoding splits the byte stream like
+/// [`AsyncRaxReader`](super::AsyncRaxReader) with the same [`Framing`] options
+/// and yields each frame as a `String`. Encoding appends a `*hh\r\n` checksum
+/// to each sentence, like
+/// [`IRaxWriter::write_sentence`](super::IRaxWriter::write_sentence).
+///
+/// Oversized frames and, unless the framing replaces or skips it, invalid
+/// UTF-8 are reported as [`io::ErrorKind::InvalidData`], which ends a
+/// `FramedRead` stream.
+#[derive(Debug)]
+pub struct NmeaCodec {
+    framer: Framer,
+}
+
+impl Default for NmeaCodec {
+    fn default() -> Self { Self::new() }
+}
+
+impl NmeaCodec {
+    /// Frames start at `$` or `!`, end at `\n`, and may be at most 1024
+    /// bytes long.
+    pub fn new() -> Self { Self::with_framing(Framing::lines().start_markers(b"$!").max_len(1024)) }
+    pub fn with_framing(framing: Framing) -> Self {
+        Self {
+            framer: Framer::new(framing),
+        }
+    }
+
+    /// Counters of frames decoded and bytes dropped so far.
+    pub fn stats(&self) -> ReaderStats { self.framer.stats() }
+
+    fn take_frame(&mut self) -> io::Result<String> {
+        self.framer
+            .take_string()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+}
+
+impl Decoder for NmeaCodec {
+    type Item = String;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<String>> {
+        while !src.is_empty() {
+            let (used, event) = self.framer.feed(src);
+            src.advance(used);
+            match event {
+                Some(FrameEvent::Complete) => {
+                    let frame = self.take_frame()?;
+                    clerk::debug!("[NmeaCodec] decode: {:?}", frame);
+                    return Ok(Some(frame));
+                }
+                Some(FrameEvent::Oversized(e)) => {
+                    return Err(io::Error::new(io::ErrorKind::InvalidData, e));
+                }
+                None => {}
+            }
+        }
+        Ok(None)
+    }
+
+    fn decode_eof(&mut self, src: &mut BytesMut) -> io::Result<Option<String>> {
+        if let Some(frame) = self.decode(src)? {
+            return Ok(Some(frame));
+        }
+        if self.framer.finish() {
+            return self.take_frame().map(Some);
+        }
+        Ok(None)
+    }
+}
+
+impl<T: AsRef<str>> Encoder<T> for NmeaCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, sentence: T, dst: &mut BytesMut) -> io::Result<()> {
+        let mut buf = String::new();
+        frame_sentence(&mut buf, sentence.as_ref())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e.to_string()))?;
+        clerk::debug!("[NmeaCodec] encode: {:?}", buf);
+        dst.extend_from_slice(buf.as_bytes());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+    use futures_util::{SinkExt, TryStreamExt};
+    use tokio_util::codec::{FramedRead, FramedWrite};
+
+    use super::*;
+
+    #[test]
+    fn test_decode_partial() -> io::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut codec = NmeaCodec::new();
+        let mut buf = BytesMut::from(&b"noise$GPGGA,1*4"[..]);
+        assert_eq!(codec.decode(&mut buf)?, None);
+        buf.extend_from_slice(b"B\r\n!AIVDM");
+        assert_eq!(codec.decode(&mut buf)?.as_deref(), Some("$GPGGA,1*4B\r\n"));
+        assert_eq!(codec.decode(&mut buf)?, None);
+        assert_eq!(codec.decode_eof(&mut buf)?.as_deref(), Some("!AIVDM"));
+        assert_eq!(codec.decode_eof(&mut buf)?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_oversized() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut codec = NmeaCodec::with_framing(Framing::lines().max_len(4));
+        let mut buf = BytesMut::from(&b"toolong\n"[..]);
+        let err = codec.decode(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(codec.stats().oversized_frames, 1);
+    }
+
+    #[tokio::test]
+    async fn test_framed() -> io::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut sink = FramedWrite::new(Vec::new(), NmeaCodec::new());
+        sink.send("$GPGGA,1").await?;
+        sink.send(String::from("$EIGPQ,RMC")).await?;
+        assert!(sink.send("$GPGGA,1*4B").await.is_err());
+        let written = sink.into_inner();
+        assert_eq!(written, b"$GPGGA,1*4B\r\n$EIGPQ,RMC*3A\r\n");
+
+        let frames: Vec<String> = FramedRead::new(written.as_slice(), NmeaCodec::new())
+            .try_collect()
+            .await?;
+        assert_eq!(frames, ["$GPGGA,1*4B\r\n", "$EIGPQ,RMC*3A\r\n"]);
+        Ok(())
+    }
+}
@@ -0,0 +1,543 @@
+use std::fmt;
+
+use miette::Diagnostic;
+
+/// How a reader splits its byte stream into frames.
+///
+/// The default splits on `\n` and keeps the delimiter, like
+/// `BufRead::read_line`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Framing {
+    delimiter: Vec<u8>,
+    normalize_crlf: bool,
+    start_markers: Vec<u8>,
+    max_len: Option<usize>,
+    invalid_utf8: InvalidUtf8,
+}
+
+impl Default for Framing {
+    fn default() -> Self { Self::lines() }
+}
+
+impl Framing {
+    /// Frames terminated by `\n`.
+    pub fn lines() -> Self {
+        Self {
+            delimiter: b"\n".to_vec(),
+            normalize_crlf: false,
+            start_markers: Vec::new(),
+            max_len: None,
+            invalid_utf8: InvalidUtf8::Error,
+        }
+    }
+    /// Terminate frames with `delimiter` instead of `\n`.
+    ///
+    /// # Panics
+    /// If `delimiter` is empty.
+    pub fn delimiter(mut self, delimiter: &[u8]) -> Self {
+        assert!(!delimiter.is_empty(), "frame delimiter must not be empty");
+        self.delimiter = delimiter.to_vec();
+        self
+    }
+    /// Turn a trailing `\r\n` into `\n`.
+    pub fn normalize_crlf(mut self, normalize: bool) -> Self {
+        self.normalize_crlf = normalize;
+        self
+    }
+    /// Only start a frame at one of these bytes, e.g. `b"$!"` for NMEA.
+    /// Anything before a marker is dropped.
+    pub fn start_markers(mut self, markers: &[u8]) -> Self {
+        self.start_markers = markers.to_vec();
+        self
+    }
+    /// Refuse frames longer than `max_len` bytes, delimiter included. An
+    /// oversized frame is reported as [`OversizedFrameError`] and the rest of
+    /// it is skipped.
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+    /// What to do with frames that are not valid UTF-8. Fails the read with
+    /// [`InvalidUtf8Error`] by default.
+    pub fn invalid_utf8(mut self, invalid_utf8: InvalidUtf8) -> Self {
+        self.invalid_utf8 = invalid_utf8;
+        self
+    }
+}
+
+/// How a reader handles frames that are not valid UTF-8, e.g. after line
+/// noise on a serial link.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidUtf8 {
+    /// Fail the read. The frame is dropped and the next read continues
+    /// with the following frame.
+    #[default]
+    Error,
+    /// Replace every invalid sequence with `U+FFFD`. Suits checksummed
+    /// protocols such as NMEA: line noise shows up as invalid UTF-8, and the
+    /// checksum rejects the frame later.
+    Replace,
+    /// Drop invalid sequences and keep the rest of the frame.
+    Skip,
+}
+
+/// Counters a reader keeps about the data it received.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReaderStats {
+    /// Frames returned to the caller.
+    pub frames: u64,
+    /// Bytes that did not end up in a returned frame: data before a start
+    /// marker, oversized frames, and skipped or rejected invalid UTF-8.
+    pub dropped_bytes: u64,
+    /// Invalid UTF-8 sequences, however they were handled.
+    pub invalid_sequences: u64,
+    /// Frames rejected for exceeding [`Framing::max_len`].
+    pub oversized_frames: u64,
+}
+
+impl std::ops::AddAssign for ReaderStats {
+    fn add_assign(&mut self, other: Self) {
+        self.frames += other.frames;
+        self.dropped_bytes += other.dropped_bytes;
+        self.invalid_sequences += other.invalid_sequences;
+        self.oversized_frames += other.oversized_frames;
+    }
+}
+
+/// Error returned when a frame grows beyond [`Framing::max_len`].
+///
+/// The error is recoverable: the reader skips to the end of the offending
+/// frame and the next read returns the following frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Diagnostic)]
+#[diagnostic(
+    code(rax::io::oversized_frame),
+    help("the rest of the frame is skipped, reading can continue")
+)]
+pub struct OversizedFrameError {
+    len: usize,
+    max: usize,
+}
+
+impl OversizedFrameError {
+    /// Number of bytes seen when the frame was rejected.
+    pub fn frame_len(&self) -> usize { self.len }
+    /// The configured maximum frame length.
+    pub fn max(&self) -> usize { self.max }
+}
+
+impl fmt::Display for OversizedFrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame of at least {} bytes exceeds the maximum of {}",
+            self.len, self.max
+        )
+    }
+}
+
+impl std::error::Error for OversizedFrameError {}
+
+/// Error returned for a frame that is not valid UTF-8 under
+/// [`InvalidUtf8::Error`].
+///
+/// The error is recoverable: the frame is dropped and the next read returns
+/// the following frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Diagnostic)]
+#[diagnostic(
+    code(rax::io::invalid_utf8),
+    help("the frame is dropped, reading can continue")
+)]
+pub struct InvalidUtf8Error {
+    len: usize,
+    source: std::str::Utf8Error,
+}
+
+impl InvalidUtf8Error {
+    /// Length of the dropped frame in bytes.
+    pub fn frame_len(&self) -> usize { self.len }
+    /// Where the first invalid sequence starts.
+    pub fn valid_up_to(&self) -> usize { self.source.valid_up_to() }
+}
+
+impl fmt::Display for InvalidUtf8Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "frame of {} bytes is not valid UTF-8: {}",
+            self.len, self.source
+        )
+    }
+}
+
+impl std::error::Error for InvalidUtf8Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> { Some(&self.source) }
+}
+
+/// What a call to [`Framer::feed`] produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FrameEvent {
+    Complete,
+    Oversized(OversizedFrameError),
+}
+
+/// Frame splitting state shared by the sync and async readers.
+#[derive(Debug)]
+pub(crate) struct Framer {
+    framing: Framing,
+    frame: Vec<u8>,
+    discarding: bool,
+    /// Last bytes dropped while discarding, so a delimiter split across
+    /// chunks still ends the oversized frame.
+    tail: Vec<u8>,
+    /// The frame was handed out by reference and is cleared on the next feed.
+    lent: bool,
+    stats: ReaderStats,
+}
+
+impl Framer {
+    pub(crate) fn new(framing: Framing) -> Self {
+        Self {
+            framing,
+            frame: Vec::new(),
+            discarding: false,
+            tail: Vec::new(),
+            lent: false,
+            stats: ReaderStats::default(),
+        }
+    }
+
+    pub(crate) fn stats(&self) -> ReaderStats { self.stats }
+
+    /// Consume bytes from `chunk`, returning how many were used and whether a
+    /// frame was completed or rejected.
+    pub(crate) fn feed(&mut self, chunk: &[u8]) -> (usize, Option<FrameEvent>) {
+        if self.lent {
+            self.frame.clear();
+            self.lent = false;
+        }
+        let mut start = 0;
+        if !self.discarding && self.frame.is_empty() && !self.framing.start_markers.is_empty() {
+            match chunk
+                .iter()
+                .position(|b| self.framing.start_markers.contains(b))
+            {
+                Some(i) => start = i,
+                None => {
+                    clerk::trace!(
+                        "[Framer] dropping {} bytes before start marker",
+                        chunk.len()
+                    );
+                    self.stats.dropped_bytes += chunk.len() as u64;
+                    return (chunk.len(), None);
+                }
+            }
+            self.stats.dropped_bytes += start as u64;
+        }
+        let rest = &chunk[start..];
+        let end = self.find_delimiter_end(rest);
+        let take = end.unwrap_or(rest.len());
+
+        if self.discarding {
+            self.stats.dropped_bytes += take as u64;
+            if end.is_some() {
+                clerk::debug!("[Framer] end of oversized frame reached");
+                self.discarding = false;
+                self.tail.clear();
+            } else {
+                let keep = self.framing.delimiter.len() - 1;
+                Self::keep_tail(&mut self.tail, keep, &rest[..take]);
+            }
+            return (start + take, None);
+        }
+        if let Some(max) = self.framing.max_len
+            && self.frame.len() + take > max
+        {
+            let len = self.frame.len() + take;
+            clerk::debug!("[Framer] frame of {} bytes exceeds {}", len, max);
+            self.stats.dropped_bytes += len as u64;
+            self.stats.oversized_frames += 1;
+            self.discarding = end.is_none();
+            if self.discarding {
+                let keep = self.framing.delimiter.len() - 1;
+                self.tail.clear();
+                Self::keep_tail(&mut self.tail, keep, &self.frame);
+                Self::keep_tail(&mut self.tail, keep, &rest[..take]);
+            }
+            self.frame.clear();
+            return (
+                start + take,
+                Some(FrameEvent::Oversized(OversizedFrameError { len, max })),
+            );
+        }
+        self.frame.extend_from_slice(&rest[..take]);
+        (start + take, end.map(|_| FrameEvent::Complete))
+    }
+
+    /// Called at end of input. Returns whether a trailing, undelimited frame
+    /// is pending.
+    pub(crate) fn finish(&mut self) -> bool {
+        if self.lent {
+            self.frame.clear();
+            self.lent = false;
+        }
+        self.discarding = false;
+        self.tail.clear();
+        !self.frame.is_empty()
+    }
+
+    /// Take the current frame as a string and reset for the next one.
+    pub(crate) fn take_string(&mut self) -> miette::Result<String> {
+        self.decode()?;
+        let frame = std::mem::take(&mut self.frame);
+        Ok(String::from_utf8(frame).expect("frame was decoded"))
+    }
+
+    /// Borrow the current frame as a string. The buffer is kept and reused
+    /// for the next frame.
+    pub(crate) fn frame_str(&mut self) -> miette::Result<&str> {
+        self.decode()?;
+        self.lent = true;
+        Ok(std::str::from_utf8(&self.frame).expect("frame was decoded"))
+    }
+
+    /// Borrow the current frame without decoding it.
+    pub(crate) fn frame_bytes(&mut self) -> &[u8] {
+        self.normalize();
+        self.stats.frames += 1;
+        self.lent = true;
+        &self.frame
+    }
+
+    /// Normalize the frame and make it valid UTF-8 according to
+    /// [`Framing::invalid_utf8`]. On error the frame is dropped.
+    fn decode(&mut self) -> miette::Result<()> {
+        self.normalize();
+        if let Err(e) = std::str::from_utf8(&self.frame) {
+            let invalid: Vec<usize> = self
+                .frame
+                .utf8_chunks()
+                .map(|c| c.invalid().len())
+                .filter(|&n| n > 0)
+                .collect();
+            clerk::debug!(
+                "[Framer] {} invalid UTF-8 sequence(s) in frame of {} bytes",
+                invalid.len(),
+                self.frame.len()
+            );
+            self.stats.invalid_sequences += invalid.len() as u64;
+            match self.framing.invalid_utf8 {
+                InvalidUtf8::Error => {
+                    let len = self.frame.len();
+                    self.stats.dropped_bytes += len as u64;
+                    self.frame.clear();
+                    return Err(InvalidUtf8Error { len, source: e }.into());
+                }
+                InvalidUtf8::Replace => {
+                    self.frame = String::from_utf8_lossy(&self.frame)
+                        .into_owned()
+                        .into_bytes();
+                }
+                InvalidUtf8::Skip => {
+                    self.stats.dropped_bytes += invalid.iter().sum::<usize>() as u64;
+                    let valid: Vec<u8> = self
+                        .frame
+                        .utf8_chunks()
+                        .flat_map(|c| c.valid().as_bytes())
+                        .copied()
+                        .collect();
+                    self.frame = valid;
+                }
+            }
+        }
+        self.stats.frames += 1;
+        Ok(())
+    }
+
+    fn normalize(&mut self) {
+        if self.framing.normalize_crlf && self.frame.ends_with(b"\r\n") {
+            let len = self.frame.len();
+            self.frame.remove(len - 2);
+        }
+    }
+
+    /// Append dropped bytes to `tail`, keeping only the last `keep`, as many
+    /// as a partial delimiter can span.
+    fn keep_tail(tail: &mut Vec<u8>, keep: usize, bytes: &[u8]) {
+        tail.extend_from_slice(&bytes[bytes.len().saturating_sub(keep)..]);
+        let excess = tail.len().saturating_sub(keep);
+        tail.drain(..excess);
+    }
+
+    /// Index just past the first delimiter that ends inside `chunk`. The
+    /// delimiter may start in the bytes already buffered, or dropped while
+    /// discarding.
+    fn find_delimiter_end(&self, chunk: &[u8]) -> Option<usize> {
+        let delim = &self.framing.delimiter;
+        let before = if self.discarding {
+            &self.tail
+        } else {
+            &self.frame
+        };
+        let last = delim[delim.len() - 1];
+        memchr::memchr_iter(last, chunk)
+            .find(|&i| {
+                let seen = before.len() + i + 1;
+                seen >= delim.len()
+                    && (1..delim.len()).all(|k| {
+                        let b = if i >= k {
+                            chunk[i - k]
+                        } else {
+                            before[before.len() + i - k]
+                        };
+                        b == delim[delim.len() - 1 - k]
+                    })
+            })
+            .map(|i| i + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+
+    /// Feed `chunks` one by one, collecting completed frames and oversized
+    /// lengths.
+    fn run(framing: Framing, chunks: &[&[u8]]) -> Vec<Result<String, usize>> {
+        let mut framer = Framer::new(framing);
+        let mut out = Vec::new();
+        for chunk in chunks {
+            let mut chunk = *chunk;
+            while !chunk.is_empty() {
+                let (used, event) = framer.feed(chunk);
+                chunk = &chunk[used..];
+                match event {
+                    Some(FrameEvent::Complete) => out.push(Ok(framer.take_string().unwrap())),
+                    Some(FrameEvent::Oversized(e)) => out.push(Err(e.frame_len())),
+                    None => {}
+                }
+            }
+        }
+        if framer.finish() {
+            out.push(Ok(framer.take_string().unwrap()));
+        }
+        out
+    }
+
+    #[test]
+    fn test_default_lines() {
+        init_log_with_level(LogLevel::TRACE);
+        assert_eq!(
+            run(Framing::default(), &[b"ab\ncd", b"e\nf"]),
+            [Ok("ab\n".into()), Ok("cde\n".into()), Ok("f".into())]
+        );
+    }
+
+    #[test]
+    fn test_custom_delimiter_split_across_chunks() {
+        init_log_with_level(LogLevel::TRACE);
+        let framing = Framing::lines().delimiter(b"\r\n");
+        assert_eq!(
+            run(framing, &[b"a\rb\r", b"\nc\r\n"]),
+            [Ok("a\rb\r\n".into()), Ok("c\r\n".into())]
+        );
+    }
+
+    #[test]
+    fn test_normalize_crlf() {
+        init_log_with_level(LogLevel::TRACE);
+        let framing = Framing::lines().normalize_crlf(true);
+        assert_eq!(
+            run(framing, &[b"a\r\nb\n"]),
+            [Ok("a\n".into()), Ok("b\n".into())]
+        );
+    }
+
+    #[test]
+    fn test_start_markers() {
+        init_log_with_level(LogLevel::TRACE);
+        let framing = Framing::lines().start_markers(b"$!");
+        assert_eq!(
+            run(framing, &[b"noise", b"xx$GP\n\x00!AI\n"]),
+            [Ok("$GP\n".into()), Ok("!AI\n".into())]
+        );
+    }
+
+    #[test]
+    fn test_max_len_recovers() {
+        init_log_with_level(LogLevel::TRACE);
+        let framing = Framing::lines().max_len(4);
+        assert_eq!(
+            run(framing, &[b"ok\ntoo", b"long", b"garbage\nend\n"]),
+            [Ok("ok\n".into()), Err(7), Ok("end\n".into())]
+        );
+    }
+
+    #[test]
+    fn test_max_len_delimiter_split_while_discarding() {
+        init_log_with_level(LogLevel::TRACE);
+        let framing = Framing::lines().delimiter(b"\r\n").max_len(4);
+        assert_eq!(
+            run(framing.clone(), &[b"toolong\r", b"\nok\r\n"]),
+            [Err(8), Ok("ok\r\n".into())]
+        );
+        assert_eq!(
+            run(framing, &[b"toolong", b"more\r", b"\nok\r\n"]),
+            [Err(7), Ok("ok\r\n".into())]
+        );
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        init_log_with_level(LogLevel::TRACE);
+        let data: &[u8] = b"a\xffb\xfe\xfd\n";
+        let mut framer = Framer::new(Framing::lines());
+        framer.feed(data);
+        let err = framer.take_string().unwrap_err();
+        let err = err.downcast_ref::<InvalidUtf8Error>().unwrap();
+        assert_eq!((err.frame_len(), err.valid_up_to()), (6, 1));
+        let stats = framer.stats();
+        assert_eq!((stats.frames, stats.dropped_bytes), (0, 6));
+
+        let mut framer = Framer::new(Framing::lines().invalid_utf8(InvalidUtf8::Replace));
+        framer.feed(data);
+        assert_eq!(framer.frame_str().unwrap(), "a\u{FFFD}b\u{FFFD}\u{FFFD}\n");
+
+        let mut framer = Framer::new(Framing::lines().invalid_utf8(InvalidUtf8::Skip));
+        framer.feed(data);
+        assert_eq!(framer.take_string().unwrap(), "ab\n");
+        assert_eq!(
+            framer.stats(),
+            ReaderStats {
+                frames: 1,
+                dropped_bytes: 3,
+                invalid_sequences: 3,
+                oversized_frames: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_stats() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut framer = Framer::new(Framing::lines().start_markers(b"$").max_len(4));
+        let mut chunk: &[u8] = b"xx$ok\n$toolong\n$a\n";
+        while !chunk.is_empty() {
+            let (used, event) = framer.feed(chunk);
+            chunk = &chunk[used..];
+            if event == Some(FrameEvent::Complete) {
+                framer.take_string().unwrap();
+            }
+        }
+        assert_eq!(
+            framer.stats(),
+            ReaderStats {
+                frames: 2,
+                dropped_bytes: 11,
+                invalid_sequences: 0,
+                oversized_frames: 1,
+            }
+        );
+    }
+}
@@ -0,0 +1,714 @@
+use std::collections::VecDeque;
+use std::io::{self, BufRead, BufReader, ErrorKind};
+use std::net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs, UdpSocket};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use miette::IntoDiagnostic;
+
+use super::{
+    FrameEvent, Framer, Framing, IRaxReader, ReaderStats, ReconnectPolicy, ReconnectState,
+};
+
+/// How often server threads check whether the source was dropped.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// What a network source produced, tagged with the peer it concerns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetEvent {
+    /// A complete frame.
+    Line { peer: SocketAddr, line: String },
+    /// A connection was opened or accepted.
+    Connected(SocketAddr),
+    /// The connection failed or was closed by the peer.
+    Disconnected { peer: SocketAddr, reason: String },
+    /// No frame arrived within the idle timeout; the connection was dropped.
+    IdleTimeout(SocketAddr),
+    /// Connecting failed; the next attempt starts after `retry_in`.
+    ConnectFailed {
+        attempt: usize,
+        reason: String,
+        retry_in: Duration,
+    },
+}
+
+/// A TCP stream and the frame it is assembling.
+struct Connection {
+    peer: SocketAddr,
+    stream: BufReader<TcpStream>,
+    framer: Framer,
+}
+
+impl Connection {
+    fn new(stream: TcpStream, peer: SocketAddr, framing: Framing) -> Self {
+        Self {
+            peer,
+            stream: BufReader::new(stream),
+            framer: Framer::new(framing),
+        }
+    }
+
+    /// Read until the next frame is complete or rejected. `Ok(None)` means
+    /// the peer closed the connection. A read timeout leaves a partial frame
+    /// in place, so the call can be repeated.
+    fn next_frame(&mut self) -> io::Result<Option<FrameEvent>> {
+        loop {
+            let chunk = self.stream.fill_buf()?;
+            if chunk.is_empty() {
+                return Ok(self.framer.finish().then_some(FrameEvent::Complete));
+            }
+            let (used, event) = self.framer.feed(chunk);
+            self.stream.consume(used);
+            if event.is_some() {
+                return Ok(event);
+            }
+        }
+    }
+}
+
+fn is_timeout(e: &io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut)
+}
+
+/// Implements `IRaxReader` for a source with a `next_event` method by
+/// skipping everything but lines.
+macro_rules! impl_rax_reader {
+    ($source:ty) => {
+        impl IRaxReader for $source {
+            /// Blocks until the next line from any peer. Never returns
+            /// `None`.
+            fn read_line(&mut self) -> miette::Result<Option<String>> {
+                loop {
+                    if let NetEvent::Line { line, .. } = self.next_event()? {
+                        return Ok(Some(line));
+                    }
+                }
+            }
+
+            fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>> {
+                let mut lines = Vec::with_capacity(count);
+                for _ in 0..count {
+                    match self.read_line()? {
+                        Some(line) => lines.push(line),
+                        None => break,
+                    }
+                }
+                Ok(lines)
+            }
+        }
+    };
+}
+
+/// Reads from a device that listens for TCP connections, e.g. a
+/// serial-to-Ethernet bridge.
+///
+/// The connection is reopened with the backoff of its [`ReconnectPolicy`]
+/// whenever it fails, is closed or stays silent longer than the idle
+/// timeout. [`next_event`](Self::next_event) reports these state changes
+/// next to the lines; the [`IRaxReader`] methods only return lines.
+pub struct TcpClientSource {
+    addr: String,
+    state: ReconnectState,
+    framing: Framing,
+    conn: Option<Connection>,
+    /// Counters of the connections already dropped.
+    closed_stats: ReaderStats,
+}
+
+impl TcpClientSource {
+    /// Connect to `addr`, e.g. `192.168.1.20:4001`, on the first read.
+    pub fn new(addr: impl Into<String>, policy: ReconnectPolicy) -> Self {
+        Self::with_framing(addr, policy, Framing::default())
+    }
+    pub fn with_framing(
+        addr: impl Into<String>,
+        policy: ReconnectPolicy,
+        framing: Framing,
+    ) -> Self {
+        Self {
+            addr: addr.into(),
+            state: ReconnectState::new(policy),
+            framing,
+            conn: None,
+            closed_stats: ReaderStats::default(),
+        }
+    }
+    /// Address of the device, while connected.
+    pub fn peer(&self) -> Option<SocketAddr> { self.conn.as_ref().map(|c| c.peer) }
+    pub fn is_connected(&self) -> bool { self.conn.is_some() }
+    /// Counters summed over every connection opened so far.
+    pub fn stats(&self) -> ReaderStats {
+        let mut stats = self.closed_stats;
+        if let Some(conn) = &self.conn {
+            stats += conn.framer.stats();
+        }
+        stats
+    }
+
+    /// Block until the next line or connection state change.
+    ///
+    /// Fails once `max_attempts` connection attempts in a row have failed, or
+    /// with a recoverable [`OversizedFrameError`](super::OversizedFrameError)
+    /// or [`InvalidUtf8Error`](super::InvalidUtf8Error).
+    pub fn next_event(&mut self) -> miette::Result<NetEvent> {
+        let Some(conn) = self.conn.as_mut() else {
+            return self.connect();
+        };
+        let peer = conn.peer;
+        let reason = match conn.next_frame() {
+            Ok(Some(FrameEvent::Complete)) => {
+                self.state.frame_received();
+                let line = conn.framer.take_string()?;
+                clerk::debug!("[TcpClientSource] {}: {:?}", peer, line);
+                return Ok(NetEvent::Line { peer, line });
+            }
+            Ok(Some(FrameEvent::Oversized(e))) => return Err(e.into()),
+            Ok(None) => "end of stream".to_string(),
+            Err(e) if is_timeout(&e) => {
+                clerk::warn!("[TcpClientSource] {} idle, reconnecting", peer);
+                self.disconnect();
+                return Ok(NetEvent::IdleTimeout(peer));
+            }
+            Err(e) => e.to_string(),
+        };
+        clerk::warn!("[TcpClientSource] {} disconnected: {}", peer, reason);
+        self.disconnect();
+        Ok(NetEvent::Disconnected { peer, reason })
+    }
+
+    fn disconnect(&mut self) {
+        if let Some(conn) = self.conn.take() {
+            self.closed_stats += conn.framer.stats();
+        }
+    }
+
+    fn connect(&mut self) -> miette::Result<NetEvent> {
+        let Some(delay) = self.state.next_attempt() else {
+            miette::bail!(
+                "giving up on {} after {} failed connection attempts",
+                self.addr,
+                self.state.attempt()
+            );
+        };
+        std::thread::sleep(delay);
+        match self.open() {
+            Ok(conn) => {
+                clerk::info!(
+                    "[TcpClientSource] connected to {} after {} attempt(s)",
+                    conn.peer,
+                    self.state.attempt()
+                );
+                let peer = conn.peer;
+                self.conn = Some(conn);
+                Ok(NetEvent::Connected(peer))
+            }
+            Err(e) => {
+                let retry_in = self.state.retry_in();
+                clerk::warn!(
+                    "[TcpClientSource] connection attempt {} to {} failed: {}, retrying in {:?}",
+                    self.state.attempt(),
+                    self.addr,
+                    e,
+                    retry_in
+                );
+                Ok(NetEvent::ConnectFailed {
+                    attempt: self.state.attempt(),
+                    reason: e.to_string(),
+                    retry_in,
+                })
+            }
+        }
+    }
+
+    fn open(&self) -> io::Result<Connection> {
+        let stream = TcpStream::connect(&self.addr)?;
+        stream.set_read_timeout(self.state.policy.idle_timeout)?;
+        let peer = stream.peer_addr()?;
+        Ok(Connection::new(stream, peer, self.framing.clone()))
+    }
+}
+
+impl_rax_reader!(TcpClientSource);
+
+/// Reads from devices that connect in, e.g. a bridge configured to push its
+/// data to a fixed host.
+///
+/// Any number of peers may be connected at once; each is served by its own
+/// thread and its lines are tagged with its address. A device that
+/// reconnects is simply accepted again. Peers are accepted once the first
+/// event is requested; until then they wait in the listen backlog.
+pub struct TcpServerSource {
+    listener: Option<TcpListener>,
+    local_addr: SocketAddr,
+    framing: Framing,
+    idle_timeout: Option<Duration>,
+    events: Option<Receiver<NetEvent>>,
+    stop: Arc<AtomicBool>,
+    acceptor: Option<JoinHandle<()>>,
+}
+
+impl TcpServerSource {
+    /// Listen on `addr`, e.g. `0.0.0.0:4001`.
+    pub fn bind(addr: impl ToSocketAddrs) -> miette::Result<Self> {
+        let listener = TcpListener::bind(addr).into_diagnostic()?;
+        let local_addr = listener.local_addr().into_diagnostic()?;
+        clerk::debug!("[TcpServerSource] listening on {}", local_addr);
+        Ok(Self {
+            listener: Some(listener),
+            local_addr,
+            framing: Framing::default(),
+            idle_timeout: None,
+            events: None,
+            stop: Arc::new(AtomicBool::new(false)),
+            acceptor: None,
+        })
+    }
+    /// How peers' byte streams are split into frames.
+    pub fn framing(mut self, framing: Framing) -> Self {
+        self.framing = framing;
+        self
+    }
+    /// Drop peers that send nothing for this long. Disabled by default.
+    pub fn idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.idle_timeout = timeout;
+        self
+    }
+    /// The address the server listens on.
+    pub fn local_addr(&self) -> SocketAddr { self.local_addr }
+
+    /// Block until the next line or peer state change.
+    pub fn next_event(&mut self) -> miette::Result<NetEvent> {
+        match self.start()?.recv() {
+            Ok(event) => Ok(event),
+            Err(_) => miette::bail!("server on {} stopped", self.local_addr),
+        }
+    }
+
+    /// Like [`next_event`](Self::next_event), but give up after `timeout`.
+    pub fn next_event_timeout(&mut self, timeout: Duration) -> miette::Result<Option<NetEvent>> {
+        match self.start()?.recv_timeout(timeout) {
+            Ok(event) => Ok(Some(event)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(RecvTimeoutError::Disconnected) => {
+                miette::bail!("server on {} stopped", self.local_addr)
+            }
+        }
+    }
+
+    /// Spawn the accept thread on first use.
+    fn start(&mut self) -> miette::Result<&Receiver<NetEvent>> {
+        if let Some(listener) = self.listener.take() {
+            listener.set_nonblocking(true).into_diagnostic()?;
+            let (tx, rx) = mpsc::channel();
+            let framing = self.framing.clone();
+            let idle_timeout = self.idle_timeout;
+            let stop = self.stop.clone();
+            self.acceptor = Some(std::thread::spawn(move || {
+                accept(&listener, &framing, idle_timeout, &tx, &stop)
+            }));
+            self.events = Some(rx);
+        }
+        Ok(self.events.as_ref().expect("server is started"))
+    }
+}
+
+impl Drop for TcpServerSource {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(acceptor) = self.acceptor.take() {
+            let _ = acceptor.join();
+        }
+    }
+}
+
+impl_rax_reader!(TcpServerSource);
+
+/// Accept peers until `stop` is set, then wait for their threads to end.
+fn accept(
+    listener: &TcpListener,
+    framing: &Framing,
+    idle_timeout: Option<Duration>,
+    tx: &Sender<NetEvent>,
+    stop: &Arc<AtomicBool>,
+) {
+    let mut peers: Vec<JoinHandle<()>> = Vec::new();
+    while !stop.load(Ordering::Relaxed) {
+        peers.retain(|peer| !peer.is_finished());
+        let (stream, peer) = match listener.accept() {
+            Ok(accepted) => accepted,
+            Err(e) if is_timeout(&e) => {
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+            Err(e) => {
+                clerk::warn!("[TcpServerSource] accept failed: {}", e);
+                std::thread::sleep(POLL_INTERVAL);
+                continue;
+            }
+        };
+        // Short read timeouts let the peer thread notice `stop`.
+        if let Err(e) = stream
+            .set_nonblocking(false)
+            .and_then(|()| stream.set_read_timeout(Some(POLL_INTERVAL)))
+        {
+            clerk::warn!("[TcpServerSource] dropping {}: {}", peer, e);
+            continue;
+        }
+        clerk::info!("[TcpServerSource] {} connected", peer);
+        if tx.send(NetEvent::Connected(peer)).is_err() {
+            break;
+        }
+        let conn = Connection::new(stream, peer, framing.clone());
+        let tx = tx.clone();
+        let stop = stop.clone();
+        peers.push(std::thread::spawn(move || {
+            serve(conn, idle_timeout, &tx, &stop)
+        }));
+    }
+    for peer in peers {
+        let _ = peer.join();
+    }
+}
+
+/// Forward the lines of one peer until it disconnects or `stop` is set.
+fn serve(
+    mut conn: Connection,
+    idle_timeout: Option<Duration>,
+    tx: &Sender<NetEvent>,
+    stop: &AtomicBool,
+) {
+    let peer = conn.peer;
+    let mut last_frame = Instant::now();
+    let event = loop {
+        if stop.load(Ordering::Relaxed) {
+            return;
+        }
+        match conn.next_frame() {
+            Ok(Some(FrameEvent::Complete)) => {
+                last_frame = Instant::now();
+                match conn.framer.take_string() {
+                    Ok(line) => {
+                        clerk::debug!("[TcpServerSource] {}: {:?}", peer, line);
+                        if tx.send(NetEvent::Line { peer, line }).is_err() {
+                            return;
+                        }
+                    }
+                    Err(e) => clerk::warn!("[TcpServerSource] {}: {}", peer, e),
+                }
+            }
+            Ok(Some(FrameEvent::Oversized(e))) => clerk::warn!("[TcpServerSource] {}: {}", peer, e),
+            Ok(None) => {
+                break NetEvent::Disconnected {
+                    peer,
+                    reason: "end of stream".to_string(),
+                };
+            }
+            Err(e) if is_timeout(&e) => {
+                if idle_timeout.is_some_and(|t| last_frame.elapsed() >= t) {
+                    break NetEvent::IdleTimeout(peer);
+                }
+            }
+            Err(e) => {
+                break NetEvent::Disconnected {
+                    peer,
+                    reason: e.to_string(),
+                };
+            }
+        }
+    };
+    clerk::warn!("[TcpServerSource] {:?}", event);
+    let _ = tx.send(event);
+}
+
+/// Reads NMEA broadcast over UDP, unicast or multicast.
+///
+/// Every datagram is split into frames on its own; a frame without a
+/// delimiter ends with its datagram. Lines are tagged with the sender's
+/// address.
+pub struct UdpSource {
+    socket: UdpSocket,
+    framer: Framer,
+    buf: Vec<u8>,
+    pending: VecDeque<NetEvent>,
+}
+
+impl UdpSource {
+    /// Receive datagrams sent to `addr`, e.g. `0.0.0.0:10110`.
+    pub fn bind(addr: impl ToSocketAddrs) -> miette::Result<Self> {
+        let socket = UdpSocket::bind(addr).into_diagnostic()?;
+        clerk::debug!(
+            "[UdpSource] bound to {}",
+            socket.local_addr().into_diagnostic()?
+        );
+        Ok(Self {
+            socket,
+            framer: Framer::new(Framing::default()),
+            buf: vec![0; 65536],
+            pending: VecDeque::new(),
+        })
+    }
+    /// Receive datagrams sent to the multicast `group` on `port`, on the
+    /// default interface.
+    pub fn multicast(group: Ipv4Addr, port: u16) -> miette::Result<Self> {
+        let source = Self::bind((Ipv4Addr::UNSPECIFIED, port))?;
+        source
+            .socket
+            .join_multicast_v4(&group, &Ipv4Addr::UNSPECIFIED)
+            .into_diagnostic()?;
+        clerk::debug!("[UdpSource] joined {}", group);
+        Ok(source)
+    }
+    /// How datagrams are split into frames.
+    pub fn framing(mut self, framing: Framing) -> Self {
+        self.framer = Framer::new(framing);
+        self
+    }
+    pub fn local_addr(&self) -> miette::Result<SocketAddr> {
+        self.socket.local_addr().into_diagnostic()
+    }
+    /// Counters of frames read and bytes dropped so far.
+    pub fn stats(&self) -> ReaderStats { self.framer.stats() }
+    /// The underlying socket, e.g. to set a read timeout or join further
+    /// groups.
+    pub fn socket(&self) -> &UdpSocket { &self.socket }
+
+    /// Block until the next line. Only [`NetEvent::Line`] is produced;
+    /// oversized frames and invalid UTF-8 are logged and skipped.
+    pub fn next_event(&mut self) -> miette::Result<NetEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+            let (len, peer) = self.socket.recv_from(&mut self.buf).into_diagnostic()?;
+            clerk::trace!("[UdpSource] {} bytes from {}", len, peer);
+            let mut pos = 0;
+            while pos < len {
+                let (used, event) = self.framer.feed(&self.buf[pos..len]);
+                pos += used;
+                match event {
+                    Some(FrameEvent::Complete) => self.push_frame(peer),
+                    Some(FrameEvent::Oversized(e)) => clerk::warn!("[UdpSource] {}: {}", peer, e),
+                    None => {}
+                }
+            }
+            if self.framer.finish() {
+                self.push_frame(peer);
+            }
+        }
+    }
+
+    fn push_frame(&mut self, peer: SocketAddr) {
+        match self.framer.take_string() {
+            Ok(line) => {
+                clerk::debug!("[UdpSource] {}: {:?}", peer, line);
+                self.pending.push_back(NetEvent::Line { peer, line });
+            }
+            Err(e) => clerk::warn!("[UdpSource] {}: {}", peer, e),
+        }
+    }
+}
+
+impl_rax_reader!(UdpSource);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::io::Write;
+
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+
+    fn policy() -> ReconnectPolicy {
+        ReconnectPolicy {
+            idle_timeout: Some(Duration::from_millis(200)),
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            multiplier: 2,
+            max_attempts: Some(2),
+        }
+    }
+
+    /// Collect events until `n` lines were seen.
+    fn lines_from(server: &mut TcpServerSource, n: usize) -> miette::Result<Vec<NetEvent>> {
+        let mut lines = Vec::new();
+        while lines.len() < n {
+            match server.next_event_timeout(Duration::from_secs(5))? {
+                Some(event @ NetEvent::Line { .. }) => lines.push(event),
+                Some(_) => {}
+                None => miette::bail!("timed out"),
+            }
+        }
+        Ok(lines)
+    }
+
+    #[test]
+    fn test_tcp_client_reconnects() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let listener = TcpListener::bind("127.0.0.1:0").into_diagnostic()?;
+        let addr = listener.local_addr().into_diagnostic()?;
+        let device = std::thread::spawn(move || {
+            for data in ["$A\r\n$B\r\n", "$C"] {
+                let (mut stream, _) = listener.accept().unwrap();
+                stream.write_all(data.as_bytes()).unwrap();
+            }
+        });
+        let mut source = TcpClientSource::new(addr.to_string(), policy());
+        let mut events = Vec::new();
+        for _ in 0..7 {
+            events.push(source.next_event()?);
+        }
+        device.join().unwrap();
+        let line = |line: &str| NetEvent::Line {
+            peer: addr,
+            line: line.to_string(),
+        };
+        let disconnected = NetEvent::Disconnected {
+            peer: addr,
+            reason: "end of stream".to_string(),
+        };
+        assert_eq!(
+            events,
+            [
+                NetEvent::Connected(addr),
+                line("$A\r\n"),
+                line("$B\r\n"),
+                disconnected.clone(),
+                NetEvent::Connected(addr),
+                line("$C"),
+                disconnected,
+            ]
+        );
+        assert_eq!(source.stats().frames, 3);
+        Ok(())
+    }
+
+    #[test]
+    fn test_tcp_client_idle_and_gives_up() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let listener = TcpListener::bind("127.0.0.1:0").into_diagnostic()?;
+        let addr = listener.local_addr().into_diagnostic()?;
+        let mut source = TcpClientSource::new(addr.to_string(), policy());
+        assert_eq!(source.next_event()?, NetEvent::Connected(addr));
+        // The device accepts but never sends.
+        let (_stream, _) = listener.accept().into_diagnostic()?;
+        assert_eq!(source.next_event()?, NetEvent::IdleTimeout(addr));
+        assert!(!source.is_connected());
+
+        // The silent connection counts as the first failed attempt.
+        drop(listener);
+        assert!(matches!(
+            source.next_event()?,
+            NetEvent::ConnectFailed { attempt: 2, .. }
+        ));
+        assert!(source.next_event().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tcp_client_backs_off_when_closed_at_once() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let listener = TcpListener::bind("127.0.0.1:0").into_diagnostic()?;
+        let addr = listener.local_addr().into_diagnostic()?;
+        // The device accepts and closes every connection right away.
+        let device = std::thread::spawn(move || {
+            for _ in 0..2 {
+                drop(listener.accept().unwrap());
+            }
+        });
+        let mut source = TcpClientSource::new(addr.to_string(), policy());
+        let mut connects = 0;
+        let err = loop {
+            match source.next_event() {
+                Ok(NetEvent::Connected(_)) => connects += 1,
+                Ok(_) => {}
+                Err(e) => break e,
+            }
+        };
+        device.join().unwrap();
+        assert_eq!(connects, 2);
+        assert!(
+            err.to_string()
+                .contains("after 2 failed connection attempts")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tcp_server_tags_peers() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut server = TcpServerSource::bind("127.0.0.1:0")?;
+        let mut a = TcpStream::connect(server.local_addr()).into_diagnostic()?;
+        let mut b = TcpStream::connect(server.local_addr()).into_diagnostic()?;
+        a.write_all(b"$A\r\n").into_diagnostic()?;
+        b.write_all(b"$B\r\n").into_diagnostic()?;
+        let peers: HashMap<String, SocketAddr> = lines_from(&mut server, 2)?
+            .into_iter()
+            .map(|event| match event {
+                NetEvent::Line { peer, line } => (line, peer),
+                other => panic!("expected a line, got {other:?}"),
+            })
+            .collect();
+        assert_eq!(peers.len(), 2);
+        assert_eq!(peers["$A\r\n"], a.local_addr().into_diagnostic()?);
+        assert_eq!(peers["$B\r\n"], b.local_addr().into_diagnostic()?);
+
+        // A device that reconnects is accepted again.
+        drop(a);
+        let mut a = TcpStream::connect(server.local_addr()).into_diagnostic()?;
+        a.write_all(b"$C\r\n").into_diagnostic()?;
+        let peer = a.local_addr().into_diagnostic()?;
+        assert_eq!(
+            lines_from(&mut server, 1)?,
+            [NetEvent::Line {
+                peer,
+                line: "$C\r\n".to_string()
+            }]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_tcp_server_idle_timeout() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut server =
+            TcpServerSource::bind("127.0.0.1:0")?.idle_timeout(Some(Duration::from_millis(100)));
+        let client = TcpStream::connect(server.local_addr()).into_diagnostic()?;
+        let peer = client.local_addr().into_diagnostic()?;
+        let timeout = Duration::from_secs(5);
+        assert_eq!(
+            server.next_event_timeout(timeout)?,
+            Some(NetEvent::Connected(peer))
+        );
+        assert_eq!(
+            server.next_event_timeout(timeout)?,
+            Some(NetEvent::IdleTimeout(peer))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_udp_datagrams() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut source =
+            UdpSource::bind("127.0.0.1:0")?.framing(Framing::lines().start_markers(b"$!"));
+        let sender = UdpSocket::bind("127.0.0.1:0").into_diagnostic()?;
+        let peer = sender.local_addr().into_diagnostic()?;
+        let target = source.local_addr()?;
+        sender.send_to(b"$A\r\nnoise$B", target).into_diagnostic()?;
+        sender.send_to(b"!C\r\n", target).into_diagnostic()?;
+        assert_eq!(
+            source.next_event()?,
+            NetEvent::Line {
+                peer,
+                line: "$A\r\n".to_string()
+            }
+        );
+        assert_eq!(source.read_lines_by_count(2)?, ["$B", "!C\r\n"]);
+        assert_eq!(source.stats().dropped_bytes, 5);
+        Ok(())
+    }
+}
@@ -2,6 +2,8 @@ use std::io::BufRead;
 
 use miette::IntoDiagnostic;
 
+use super::{FrameEvent, Framer, Framing, ReaderStats};
+
 /// Trait for reading lines from a source.
 pub trait IRaxReader {
     /// Reads the next line; returns `None` on EOF.
@@ -10,18 +12,59 @@ pub trait IRaxReader {
     fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>>;
 }
 
+/// Readers that can lend lines out of a buffer they own, without allocating.
+pub trait IRaxLendingReader: IRaxReader {
+    /// Reads the next line into a buffer owned by the reader and lends it
+    /// out; returns `None` on EOF. The line is only valid until the next
+    /// read.
+    fn read_line_ref(&mut self) -> miette::Result<Option<&str>>;
+}
+
 /// A buffered line reader that implements `IRaxReader`.
 pub struct RaxReader<R: BufRead> {
     inner: R,
-    buf: String,
+    framer: Framer,
 }
 
 impl<R: BufRead> RaxReader<R> {
     /// Create a new `RaxReader` from a type implementing `BufRead`.
-    pub fn new(inner: R) -> Self {
+    pub fn new(inner: R) -> Self { Self::with_framing(inner, Framing::default()) }
+    /// Create a `RaxReader` that splits its input according to `framing`.
+    pub fn with_framing(inner: R, framing: Framing) -> Self {
         Self {
             inner,
-            buf: String::new(),
+            framer: Framer::new(framing),
+        }
+    }
+
+    /// Counters of frames read and bytes dropped so far.
+    pub fn stats(&self) -> ReaderStats { self.framer.stats() }
+
+    /// Raw-bytes variant of `read_line_ref`: the frame is returned as is,
+    /// without UTF-8 validation. Returns `None` on EOF.
+    pub fn read_frame_bytes(&mut self) -> miette::Result<Option<&[u8]>> {
+        if !self.next_frame()? {
+            return Ok(None);
+        }
+        let frame = self.framer.frame_bytes();
+        clerk::debug!("[RaxReader] read_frame_bytes: {} bytes", frame.len());
+        Ok(Some(frame))
+    }
+
+    /// Read until the next frame is complete. Returns `false` on EOF.
+    fn next_frame(&mut self) -> miette::Result<bool> {
+        loop {
+            let chunk = self.inner.fill_buf().into_diagnostic()?;
+            if chunk.is_empty() {
+                return Ok(self.framer.finish());
+            }
+            let (used, event) = self.framer.feed(chunk);
+            self.inner.consume(used);
+            match event {
+                Some(FrameEvent::Complete) => return Ok(true),
+                Some(FrameEvent::Oversized(e)) => return Err(e.into()),
+                None => {}
+            }
         }
     }
 }
@@ -29,17 +72,18 @@ impl<R: BufRead> RaxReader<R> {
 impl<R: BufRead> IRaxReader for RaxReader<R> {
     /// Reads a single line from the inner reader.
     /// Returns `Ok(Some(line))` if a line is read, or `Ok(None)` on EOF.
+    /// Fails with [`OversizedFrameError`](super::OversizedFrameError) if the
+    /// framing limits the frame length, or with
+    /// [`InvalidUtf8Error`](super::InvalidUtf8Error) for a frame that is not
+    /// valid UTF-8; the next call continues after the offending frame.
     fn read_line(&mut self) -> miette::Result<Option<String>> {
-        let mut buf = String::new();
-        self.buf.clear();
-        let n = self.inner.read_line(&mut buf).into_diagnostic()?;
-        // Log the number of bytes read and the line content (for debugging)
-        clerk::debug!(
-            "[RaxReader] read_line: bytes read = {}, line = {:?}",
-            n,
-            buf
-        );
-        if n == 0 { Ok(None) } else { Ok(Some(buf)) }
+        if !self.next_frame()? {
+            return Ok(None);
+        }
+        let line = self.framer.take_string()?;
+        // Log the line content (for debugging)
+        clerk::debug!("[RaxReader] read_line: line = {:?}", line);
+        Ok(Some(line))
     }
 
     /// Reads up to `count` lines from the inner reader.
@@ -64,6 +108,19 @@ impl<R: BufRead> IRaxReader for RaxReader<R> {
     }
 }
 
+impl<R: BufRead> IRaxLendingReader for RaxReader<R> {
+    /// Zero-allocation variant of `read_line`; the internal buffer is reused
+    /// for every line.
+    fn read_line_ref(&mut self) -> miette::Result<Option<&str>> {
+        if !self.next_frame()? {
+            return Ok(None);
+        }
+        let line = self.framer.frame_str()?;
+        clerk::debug!("[RaxReader] read_line_ref: line = {:?}", line);
+        Ok(Some(line))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
@@ -71,6 +128,9 @@ mod tests {
     use clerk::{LogLevel, init_log_with_level};
 
     use super::*;
+    use crate::io::{InvalidUtf8, InvalidUtf8Error, OversizedFrameError};
+    use crate::str_parser::StrParserContext;
+    use crate::str_parser::rules::{UntilChar, UntilMode};
 
     #[test]
     fn test_read_line_some() {
@@ -170,4 +230,76 @@ mod tests {
         let lines = reader.read_lines_by_count(2).unwrap();
         assert_eq!(lines, vec!["log1\n".to_string(), "log2\n".to_string()]);
     }
+
+    #[test]
+    fn test_read_line_ref() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = RaxReader::new(Cursor::new("hello\nworld"));
+        assert_eq!(reader.read_line_ref().unwrap(), Some("hello\n"));
+        assert_eq!(reader.read_line_ref().unwrap(), Some("world"));
+        assert_eq!(reader.read_line_ref().unwrap(), None);
+    }
+
+    #[test]
+    fn test_read_line_ref_into_context() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = RaxReader::new(Cursor::new("a,1\nb,2\n"));
+        let mut ctx = StrParserContext::new();
+        let mut firsts = Vec::new();
+        while let Some(line) = reader.read_line_ref().unwrap() {
+            ctx.init_from_str(line);
+            firsts.push(
+                ctx.take(&UntilChar::<','> {
+                    mode: UntilMode::Discard,
+                })
+                .map(str::to_string),
+            );
+        }
+        assert_eq!(firsts, [Some("a".to_string()), Some("b".to_string())]);
+    }
+
+    #[test]
+    fn test_read_line_with_framing() {
+        init_log_with_level(LogLevel::TRACE);
+        let data = "junk$GPGGA,1\r\n$GPRMC,waytoolong\r\n!AIVDM\r\n";
+        let framing = Framing::lines()
+            .start_markers(b"$!")
+            .normalize_crlf(true)
+            .max_len(16);
+        let mut reader = RaxReader::with_framing(Cursor::new(data), framing);
+        assert_eq!(reader.read_line().unwrap(), Some("$GPGGA,1\n".to_string()));
+        let err = reader.read_line().unwrap_err();
+        let err = err.downcast_ref::<OversizedFrameError>().unwrap();
+        assert_eq!(err.max(), 16);
+        assert_eq!(reader.read_line().unwrap(), Some("!AIVDM\n".to_string()));
+        assert_eq!(reader.read_line().unwrap(), None);
+    }
+
+    #[test]
+    fn test_invalid_utf8_does_not_stop_reading() {
+        init_log_with_level(LogLevel::TRACE);
+        let data: &[u8] = b"$A\n$\xffB\n$C\n";
+        let mut reader = RaxReader::new(Cursor::new(data));
+        assert_eq!(reader.read_line().unwrap().as_deref(), Some("$A\n"));
+        let err = reader.read_line().unwrap_err();
+        assert!(err.downcast_ref::<InvalidUtf8Error>().is_some());
+        assert_eq!(reader.read_line().unwrap().as_deref(), Some("$C\n"));
+
+        let framing = Framing::lines().invalid_utf8(InvalidUtf8::Replace);
+        let mut reader = RaxReader::with_framing(Cursor::new(data), framing);
+        let lines = reader.read_lines_by_count(3).unwrap();
+        assert_eq!(lines, ["$A\n", "$\u{FFFD}B\n", "$C\n"]);
+        let stats = reader.stats();
+        assert_eq!((stats.frames, stats.invalid_sequences), (3, 1));
+    }
+
+    #[test]
+    fn test_read_frame_bytes() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = RaxReader::new(Cursor::new(&b"\xb5\x62\n$A"[..]));
+        assert_eq!(reader.read_frame_bytes().unwrap(), Some(&b"\xb5\x62\n"[..]));
+        assert_eq!(reader.read_frame_bytes().unwrap(), Some(&b"$A"[..]));
+        assert_eq!(reader.read_frame_bytes().unwrap(), None);
+        assert_eq!(reader.stats().frames, 2);
+    }
 }
@@ -2,19 +2,68 @@ use async_trait::async_trait;
 use miette::IntoDiagnostic;
 use tokio::io::{AsyncBufRead, AsyncBufReadExt};
 
+use super::{FrameEvent, Framer, Framing, ReaderStats};
+
 /// Async counterpart of `IRaxReader`.
 #[async_trait]
 pub trait AsyncIRaxReader {
     async fn read_line(&mut self) -> miette::Result<Option<String>>;
     async fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>>;
 }
+/// Async counterpart of `IRaxLendingReader`.
+#[async_trait]
+pub trait AsyncIRaxLendingReader: AsyncIRaxReader {
+    /// Lending variant of `read_line`, see
+    /// `IRaxLendingReader::read_line_ref`.
+    async fn read_line_ref(&mut self) -> miette::Result<Option<&str>>;
+}
 /// Buffered async reader implementing `AsyncIRaxReader`.
 pub struct AsyncRaxReader<R: AsyncBufRead + Unpin> {
     inner: R,
+    pub(crate) framer: Framer,
 }
 
 impl<R: AsyncBufRead + Unpin> AsyncRaxReader<R> {
-    pub fn new(inner: R) -> Self { Self { inner } }
+    pub fn new(inner: R) -> Self { Self::with_framing(inner, Framing::default()) }
+    /// Create an `AsyncRaxReader` that splits its input according to
+    /// `framing`.
+    pub fn with_framing(inner: R, framing: Framing) -> Self {
+        Self {
+            inner,
+            framer: Framer::new(framing),
+        }
+    }
+
+    /// Counters of frames read and bytes dropped so far.
+    pub fn stats(&self) -> ReaderStats { self.framer.stats() }
+
+    /// Raw-bytes variant of `read_line_ref`: the frame is returned as is,
+    /// without UTF-8 validation. Returns `None` on EOF.
+    pub async fn read_frame_bytes(&mut self) -> miette::Result<Option<&[u8]>> {
+        if !self.next_frame().await? {
+            return Ok(None);
+        }
+        let frame = self.framer.frame_bytes();
+        clerk::debug!("[AsyncRaxReader] read_frame_bytes: {} bytes", frame.len());
+        Ok(Some(frame))
+    }
+
+    /// Read until the next frame is complete. Returns `false` on EOF.
+    pub(crate) async fn next_frame(&mut self) -> miette::Result<bool> {
+        loop {
+            let chunk = self.inner.fill_buf().await.into_diagnostic()?;
+            if chunk.is_empty() {
+                return Ok(self.framer.finish());
+            }
+            let (used, event) = self.framer.feed(chunk);
+            self.inner.consume(used);
+            match event {
+                Some(FrameEvent::Complete) => return Ok(true),
+                Some(FrameEvent::Oversized(e)) => return Err(e.into()),
+                None => {}
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -23,14 +72,12 @@ where
     R: AsyncBufRead + Unpin + Send, // `Send` lets it cross await points safely
 {
     async fn read_line(&mut self) -> miette::Result<Option<String>> {
-        let mut buf = String::new();
-        let n = self.inner.read_line(&mut buf).await.into_diagnostic()?;
-        clerk::debug!(
-            "[AsyncRaxReader] read_line: bytes read = {}, line = {:?}",
-            n,
-            buf
-        );
-        Ok((n > 0).then_some(buf))
+        if !self.next_frame().await? {
+            return Ok(None);
+        }
+        let line = self.framer.take_string()?;
+        clerk::debug!("[AsyncRaxReader] read_line: line = {:?}", line);
+        Ok(Some(line))
     }
 
     async fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>> {
@@ -52,6 +99,21 @@ where
     }
 }
 
+#[async_trait]
+impl<R> AsyncIRaxLendingReader for AsyncRaxReader<R>
+where
+    R: AsyncBufRead + Unpin + Send,
+{
+    async fn read_line_ref(&mut self) -> miette::Result<Option<&str>> {
+        if !self.next_frame().await? {
+            return Ok(None);
+        }
+        let line = self.framer.frame_str()?;
+        clerk::debug!("[AsyncRaxReader] read_line_ref: line = {:?}", line);
+        Ok(Some(line))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use clerk::{LogLevel, init_log_with_level};
@@ -95,4 +157,42 @@ mod tests {
         let lines = reader.read_lines_by_count(3).await.unwrap();
         assert!(lines.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_read_line_ref() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = make_reader("foo\nbar");
+        assert_eq!(reader.read_line_ref().await.unwrap(), Some("foo\n"));
+        assert_eq!(reader.read_line_ref().await.unwrap(), Some("bar"));
+        assert_eq!(reader.read_line_ref().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_read_line_with_framing() {
+        init_log_with_level(LogLevel::TRACE);
+        let framing = Framing::lines().delimiter(b"\r\n").max_len(8);
+        let mut reader = AsyncRaxReader::with_framing(
+            BufReader::new("a\nb\r\n0123456789\r\nc\r\n".as_bytes()),
+            framing,
+        );
+        let line = reader.read_line().await.unwrap();
+        assert_eq!(line.as_deref(), Some("a\nb\r\n"));
+        assert!(reader.read_line().await.is_err());
+        let line = reader.read_line().await.unwrap();
+        assert_eq!(line.as_deref(), Some("c\r\n"));
+    }
+
+    #[tokio::test]
+    async fn test_read_frame_bytes() {
+        init_log_with_level(LogLevel::TRACE);
+        let data: &[u8] = b"\xff\n$A\n";
+        let mut reader = AsyncRaxReader::new(BufReader::new(data));
+        assert_eq!(
+            reader.read_frame_bytes().await.unwrap(),
+            Some(&b"\xff\n"[..])
+        );
+        assert_eq!(reader.read_line().await.unwrap().as_deref(), Some("$A\n"));
+        assert_eq!(reader.stats().frames, 2);
+        assert_eq!(reader.stats().invalid_sequences, 0);
+    }
 }
@@ -0,0 +1,311 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+use async_trait::async_trait;
+use tokio::sync::Notify;
+
+use super::{AsyncIRaxReader, IRaxReader, InvalidUtf8Error, OversizedFrameError};
+
+/// What a [`ReaderBridge`] does when its queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Backpressure {
+    /// Stop reading until the consumer catches up.
+    #[default]
+    Block,
+    /// Keep reading and discard the oldest queued line, e.g. for live
+    /// position data where only the latest fix matters.
+    DropOldest,
+}
+
+#[derive(Debug)]
+struct State {
+    queue: VecDeque<miette::Result<String>>,
+    /// The worker will not queue anything else.
+    done: bool,
+    /// The bridge was dropped or is shutting down.
+    stopped: bool,
+    dropped: u64,
+}
+
+#[derive(Debug)]
+struct Shared {
+    state: Mutex<State>,
+    /// Signalled to the worker when the queue has room or the bridge stops.
+    space: Condvar,
+    /// Signalled to the consumer when a line is queued or the worker ends.
+    ready: Notify,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> { self.state.lock().unwrap_or_else(|e| e.into_inner()) }
+}
+
+/// Runs a blocking [`IRaxReader`] on a dedicated thread and exposes it as an
+/// [`AsyncIRaxReader`].
+///
+/// Lines are passed through a bounded queue; when it is full the worker
+/// either waits or drops the oldest line, see [`Backpressure`]. Errors are
+/// passed on in order. After an [`OversizedFrameError`] or
+/// [`InvalidUtf8Error`] the worker keeps reading; any other error ends it,
+/// and reads return `None` once the error was delivered.
+///
+/// Dropping the bridge stops the worker. A worker blocked in a read can not
+/// be interrupted; it exits, dropping the reader, as soon as that read
+/// returns. Use [`into_inner`](Self::into_inner) to wait for that.
+pub struct ReaderBridge<R: IRaxReader + Send + 'static> {
+    shared: Arc<Shared>,
+    worker: Option<JoinHandle<R>>,
+}
+
+impl<R: IRaxReader + Send + 'static> ReaderBridge<R> {
+    /// Bridge `reader` with a queue of 64 lines that blocks when full.
+    pub fn spawn(reader: R) -> Self { Self::with_options(reader, 64, Backpressure::Block) }
+    /// Bridge `reader` with a queue of `capacity` lines.
+    ///
+    /// # Panics
+    /// If `capacity` is zero.
+    pub fn with_options(reader: R, capacity: usize, backpressure: Backpressure) -> Self {
+        assert!(capacity > 0, "bridge capacity must not be zero");
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State {
+                queue: VecDeque::with_capacity(capacity),
+                done: false,
+                stopped: false,
+                dropped: 0,
+            }),
+            space: Condvar::new(),
+            ready: Notify::new(),
+        });
+        let worker = {
+            let shared = shared.clone();
+            std::thread::spawn(move || run(reader, &shared, capacity, backpressure))
+        };
+        Self {
+            shared,
+            worker: Some(worker),
+        }
+    }
+
+    /// Number of lines discarded by [`Backpressure::DropOldest`].
+    pub fn dropped(&self) -> u64 { self.shared.lock().dropped }
+
+    /// Stop the worker and return the reader. Blocks until the worker's
+    /// current read returns.
+    pub fn into_inner(mut self) -> miette::Result<R> {
+        self.stop();
+        let worker = self.worker.take().expect("worker is running");
+        match worker.join() {
+            Ok(reader) => Ok(reader),
+            Err(_) => miette::bail!("reader thread panicked"),
+        }
+    }
+
+    fn stop(&self) {
+        self.shared.lock().stopped = true;
+        self.shared.space.notify_all();
+    }
+}
+
+impl<R: IRaxReader + Send + 'static> Drop for ReaderBridge<R> {
+    fn drop(&mut self) {
+        self.stop();
+        if let Some(worker) = self.worker.take()
+            && worker.is_finished()
+        {
+            let _ = worker.join();
+        }
+    }
+}
+
+/// Worker loop: read lines and queue them until the reader ends, fails or
+/// the bridge stops.
+fn run<R: IRaxReader>(
+    mut reader: R,
+    shared: &Shared,
+    capacity: usize,
+    backpressure: Backpressure,
+) -> R {
+    loop {
+        if shared.lock().stopped {
+            break;
+        }
+        let (item, last) = match reader.read_line() {
+            Ok(Some(line)) => (Ok(line), false),
+            Ok(None) => break,
+            Err(e) => {
+                let recoverable = e.downcast_ref::<OversizedFrameError>().is_some()
+                    || e.downcast_ref::<InvalidUtf8Error>().is_some();
+                clerk::warn!("[ReaderBridge] read failed: {}", e);
+                (Err(e), !recoverable)
+            }
+        };
+        let mut state = shared.lock();
+        while state.queue.len() >= capacity && !state.stopped {
+            match backpressure {
+                Backpressure::Block => {
+                    state = shared.space.wait(state).unwrap_or_else(|e| e.into_inner());
+                }
+                Backpressure::DropOldest => {
+                    state.queue.pop_front();
+                    state.dropped += 1;
+                    clerk::trace!("[ReaderBridge] queue full, dropped oldest line");
+                }
+            }
+        }
+        if state.stopped {
+            break;
+        }
+        state.queue.push_back(item);
+        drop(state);
+        shared.ready.notify_one();
+        if last {
+            break;
+        }
+    }
+    clerk::debug!("[ReaderBridge] worker finished");
+    shared.lock().done = true;
+    shared.ready.notify_one();
+    reader
+}
+
+#[async_trait]
+impl<R: IRaxReader + Send + 'static> AsyncIRaxReader for ReaderBridge<R> {
+    /// Waits for the next queued line. Returns `None` once the worker has
+    /// ended and the queue is drained.
+    async fn read_line(&mut self) -> miette::Result<Option<String>> {
+        loop {
+            let ready = self.shared.ready.notified();
+            {
+                let mut state = self.shared.lock();
+                if let Some(item) = state.queue.pop_front() {
+                    drop(state);
+                    self.shared.space.notify_one();
+                    return item.map(Some);
+                }
+                if state.done {
+                    return Ok(None);
+                }
+            }
+            ready.await;
+        }
+    }
+
+    async fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>> {
+        let mut lines = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.read_line().await? {
+                Some(line) => lines.push(line),
+                None => break,
+            }
+        }
+        Ok(lines)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::io::{Framing, RaxReader};
+
+    /// Endless reader returning `0`, `1`, ... and counting its reads.
+    struct Counter(Arc<AtomicUsize>);
+
+    impl IRaxReader for Counter {
+        fn read_line(&mut self) -> miette::Result<Option<String>> {
+            let n = self.0.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(1));
+            Ok(Some(n.to_string()))
+        }
+        fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>> {
+            let mut lines = Vec::with_capacity(count);
+            for _ in 0..count {
+                match self.read_line()? {
+                    Some(line) => lines.push(line),
+                    None => break,
+                }
+            }
+            Ok(lines)
+        }
+    }
+
+    fn lines(n: usize) -> RaxReader<Cursor<String>> {
+        let data: String = (0..n).map(|i| format!("{i}\n")).collect();
+        RaxReader::new(Cursor::new(data))
+    }
+
+    #[tokio::test]
+    async fn test_reads_until_eof() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut bridge = ReaderBridge::spawn(lines(3));
+        assert_eq!(bridge.read_lines_by_count(5).await?, ["0\n", "1\n", "2\n"]);
+        assert_eq!(bridge.read_line().await?, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_block() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let reads = Arc::new(AtomicUsize::new(0));
+        let mut bridge = ReaderBridge::with_options(Counter(reads.clone()), 2, Backpressure::Block);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // Two queued lines and one waiting for room.
+        assert_eq!(reads.load(Ordering::SeqCst), 3);
+        assert_eq!(bridge.read_lines_by_count(4).await?, ["0", "1", "2", "3"]);
+        assert_eq!(bridge.dropped(), 0);
+
+        let reader = bridge.into_inner()?;
+        let total = reader.0.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(reads.load(Ordering::SeqCst), total);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_drop_oldest() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut bridge = ReaderBridge::with_options(lines(10), 2, Backpressure::DropOldest);
+        while bridge.dropped() < 8 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+        assert_eq!(bridge.read_lines_by_count(5).await?, ["8\n", "9\n"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        init_log_with_level(LogLevel::TRACE);
+        let reader = RaxReader::with_framing(
+            Cursor::new(b"a\ntoolong\nb\n\xff\nc\n".to_vec()),
+            Framing::lines().max_len(4),
+        );
+        let mut bridge = ReaderBridge::spawn(reader);
+        assert_eq!(bridge.read_line().await.unwrap().as_deref(), Some("a\n"));
+        assert!(bridge.read_line().await.is_err());
+        assert_eq!(bridge.read_line().await.unwrap().as_deref(), Some("b\n"));
+        let err = bridge.read_line().await.unwrap_err();
+        assert!(err.downcast_ref::<InvalidUtf8Error>().is_some());
+        assert_eq!(bridge.read_line().await.unwrap().as_deref(), Some("c\n"));
+        assert_eq!(bridge.read_line().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_drop_stops_worker() {
+        init_log_with_level(LogLevel::TRACE);
+        let reads = Arc::new(AtomicUsize::new(0));
+        let bridge =
+            ReaderBridge::with_options(Counter(reads.clone()), 1, Backpressure::DropOldest);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(bridge);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let stopped_at = reads.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(reads.load(Ordering::SeqCst), stopped_at);
+    }
+}
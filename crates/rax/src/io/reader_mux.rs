@@ -0,0 +1,128 @@
+use miette::WrapErr;
+
+use super::IRaxReader;
+
+/// Identifies the source of a line read through a [`MuxReader`] or
+/// `AsyncMuxReader`. Ids are assigned by `add` in insertion order, starting
+/// at zero.
+pub type SourceId = usize;
+
+/// Merges several readers into one stream of `(source, line)` items.
+///
+/// Sources are read round-robin, one line at a time, and dropped once they
+/// reach EOF. Reads are blocking, so a source that has no data stalls the
+/// others; use `AsyncMuxReader` for live devices.
+pub struct MuxReader<R: IRaxReader> {
+    sources: Vec<(SourceId, R)>,
+    next_id: SourceId,
+    cursor: usize,
+}
+
+impl<R: IRaxReader> Default for MuxReader<R> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<R: IRaxReader> MuxReader<R> {
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            next_id: 0,
+            cursor: 0,
+        }
+    }
+    /// Add a source and return its id.
+    pub fn add(&mut self, reader: R) -> SourceId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sources.push((id, reader));
+        id
+    }
+    /// Number of sources that have not reached EOF yet.
+    pub fn len(&self) -> usize { self.sources.len() }
+    pub fn is_empty(&self) -> bool { self.sources.is_empty() }
+
+    /// Read the next line from the next source in turn. Returns `None` once
+    /// every source reached EOF.
+    ///
+    /// An error is tagged with the source id and leaves the source in place,
+    /// so recoverable errors such as oversized frames do not end the stream.
+    pub fn next_line(&mut self) -> miette::Result<Option<(SourceId, String)>> {
+        while !self.sources.is_empty() {
+            let idx = self.cursor % self.sources.len();
+            let (id, reader) = &mut self.sources[idx];
+            let id = *id;
+            match reader.read_line().wrap_err_with(|| format!("source {id}")) {
+                Ok(Some(line)) => {
+                    clerk::debug!("[MuxReader] next_line: source {} = {:?}", id, line);
+                    self.cursor = idx + 1;
+                    return Ok(Some((id, line)));
+                }
+                Ok(None) => {
+                    clerk::debug!("[MuxReader] source {} reached EOF", id);
+                    self.sources.remove(idx);
+                    self.cursor = idx;
+                }
+                Err(e) => {
+                    self.cursor = idx + 1;
+                    return Err(e);
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R: IRaxReader> Iterator for MuxReader<R> {
+    type Item = miette::Result<(SourceId, String)>;
+
+    fn next(&mut self) -> Option<Self::Item> { self.next_line().transpose() }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::io::{Framing, RaxReader};
+
+    #[test]
+    fn test_round_robin() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut mux = MuxReader::new();
+        let a = mux.add(RaxReader::new(Cursor::new("a1\na2\na3\n")));
+        let b = mux.add(RaxReader::new(Cursor::new("b1\n")));
+        let c = mux.add(RaxReader::new(Cursor::new("c1\nc2\n")));
+        let lines = mux.collect::<miette::Result<Vec<_>>>()?;
+        let expected = [
+            (a, "a1\n"),
+            (b, "b1\n"),
+            (c, "c1\n"),
+            (a, "a2\n"),
+            (c, "c2\n"),
+            (a, "a3\n"),
+        ];
+        assert_eq!(lines, expected.map(|(id, l)| (id, l.to_string())).to_vec());
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_keeps_source() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut mux = MuxReader::new();
+        let framing = Framing::lines().max_len(4);
+        mux.add(RaxReader::with_framing(
+            Cursor::new("toolong\nok\n"),
+            framing.clone(),
+        ));
+        mux.add(RaxReader::with_framing(Cursor::new("b\n"), framing));
+        let err = mux.next_line().unwrap_err();
+        assert!(err.to_string().contains("source 0"));
+        assert_eq!(mux.next_line()?, Some((1, "b\n".to_string())));
+        assert_eq!(mux.next_line()?, Some((0, "ok\n".to_string())));
+        assert_eq!(mux.next_line()?, None);
+        assert!(mux.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use std::future::{Future, poll_fn};
+use std::pin::Pin;
+use std::task::Poll;
+
+use miette::WrapErr;
+
+use super::{AsyncIRaxReader, SourceId};
+
+type PendingRead<R> = Pin<Box<dyn Future<Output = (R, miette::Result<Option<String>>)> + Send>>;
+
+struct Source<R> {
+    id: SourceId,
+    /// `None` while a read is in flight; the reader moves into `pending`.
+    reader: Option<R>,
+    pending: Option<PendingRead<R>>,
+}
+
+/// Async counterpart of `MuxReader`.
+///
+/// All sources are read concurrently and lines are returned as they arrive.
+/// When several sources have a line ready, polling starts after the source
+/// that was served last, so a busy source cannot starve the others.
+pub struct AsyncMuxReader<R: AsyncIRaxReader + Send + 'static> {
+    sources: Vec<Source<R>>,
+    next_id: SourceId,
+    cursor: usize,
+}
+
+impl<R: AsyncIRaxReader + Send + 'static> Default for AsyncMuxReader<R> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<R: AsyncIRaxReader + Send + 'static> AsyncMuxReader<R> {
+    pub fn new() -> Self {
+        Self {
+            sources: Vec::new(),
+            next_id: 0,
+            cursor: 0,
+        }
+    }
+    /// Add a source and return its id.
+    pub fn add(&mut self, reader: R) -> SourceId {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.sources.push(Source {
+            id,
+            reader: Some(reader),
+            pending: None,
+        });
+        id
+    }
+    /// Number of sources that have not reached EOF yet.
+    pub fn len(&self) -> usize { self.sources.len() }
+    pub fn is_empty(&self) -> bool { self.sources.is_empty() }
+
+    /// Wait for the next line from any source. Returns `None` once every
+    /// source reached EOF.
+    ///
+    /// An error is tagged with the source id and leaves the source in place.
+    /// Cancelling the returned future is safe: reads in flight are kept and
+    /// resumed by the next call.
+    pub async fn next_line(&mut self) -> miette::Result<Option<(SourceId, String)>> {
+        poll_fn(|cx| {
+            let mut i = 0;
+            while i < self.sources.len() {
+                let idx = (self.cursor + i) % self.sources.len();
+                let source = &mut self.sources[idx];
+                let id = source.id;
+                if let Some(mut reader) = source.reader.take() {
+                    source.pending = Some(Box::pin(async move {
+                        let result = reader.read_line().await;
+                        (reader, result)
+                    }));
+                }
+                let pending = source.pending.as_mut().expect("a read is in flight");
+                let Poll::Ready((reader, result)) = pending.as_mut().poll(cx) else {
+                    i += 1;
+                    continue;
+                };
+                source.pending = None;
+                match result.wrap_err_with(|| format!("source {id}")) {
+                    Ok(Some(line)) => {
+                        clerk::debug!("[AsyncMuxReader] next_line: source {} = {:?}", id, line);
+                        source.reader = Some(reader);
+                        self.cursor = idx + 1;
+                        return Poll::Ready(Ok(Some((id, line))));
+                    }
+                    Ok(None) => {
+                        clerk::debug!("[AsyncMuxReader] source {} reached EOF", id);
+                        self.sources.remove(idx);
+                        if idx < self.cursor {
+                            self.cursor -= 1;
+                        }
+                    }
+                    Err(e) => {
+                        source.reader = Some(reader);
+                        self.cursor = idx + 1;
+                        return Poll::Ready(Err(e));
+                    }
+                }
+            }
+            if self.sources.is_empty() {
+                Poll::Ready(Ok(None))
+            } else {
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+    use miette::IntoDiagnostic;
+    use tokio::io::{AsyncWriteExt, BufReader};
+
+    use super::*;
+    use crate::io::AsyncRaxReader;
+
+    #[tokio::test]
+    async fn test_fair_when_all_ready() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut mux = AsyncMuxReader::new();
+        let a = mux.add(AsyncRaxReader::new(BufReader::new(
+            "a1\na2\na3\n".as_bytes(),
+        )));
+        let b = mux.add(AsyncRaxReader::new(BufReader::new("b1\nb2\n".as_bytes())));
+        let mut lines = Vec::new();
+        while let Some(item) = mux.next_line().await? {
+            lines.push(item);
+        }
+        let expected = [
+            (a, "a1\n"),
+            (b, "b1\n"),
+            (a, "a2\n"),
+            (b, "b2\n"),
+            (a, "a3\n"),
+        ];
+        assert_eq!(lines, expected.map(|(id, l)| (id, l.to_string())).to_vec());
+        assert!(mux.is_empty());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_slow_source_does_not_block() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let (slow, mut slow_device) = tokio::io::duplex(64);
+        let (fast, mut fast_device) = tokio::io::duplex(64);
+        let mut mux = AsyncMuxReader::new();
+        let s = mux.add(AsyncRaxReader::new(BufReader::new(slow)));
+        let f = mux.add(AsyncRaxReader::new(BufReader::new(fast)));
+        fast_device.write_all(b"f1\n").await.into_diagnostic()?;
+        drop(fast_device);
+        assert_eq!(mux.next_line().await?, Some((f, "f1\n".to_string())));
+        slow_device.write_all(b"s1\n").await.into_diagnostic()?;
+        drop(slow_device);
+        assert_eq!(mux.next_line().await?, Some((s, "s1\n".to_string())));
+        assert_eq!(mux.next_line().await?, None);
+        Ok(())
+    }
+}
@@ -0,0 +1,372 @@
+use std::future::Future;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use miette::IntoDiagnostic;
+use tokio::io::{AsyncBufRead, BufReader};
+use tokio_serial::{SerialPortBuilderExt, SerialStream};
+
+use super::{
+    AsyncIRaxLendingReader, AsyncIRaxReader, AsyncRaxReader, Framing, OversizedFrameError,
+    ReaderStats, ReconnectPolicy, ReconnectState,
+};
+
+/// Opens (and reopens) the stream behind a [`ResilientReader`].
+///
+/// Implemented for serial ports by [`SerialConnector`] and for any
+/// `FnMut() -> impl Future<Output = miette::Result<S>>`.
+#[async_trait]
+pub trait IAsyncConnector: Send {
+    type Stream: AsyncBufRead + Unpin + Send;
+    async fn connect(&mut self) -> miette::Result<Self::Stream>;
+}
+
+#[async_trait]
+impl<F, Fut, S> IAsyncConnector for F
+where
+    F: FnMut() -> Fut + Send,
+    Fut: Future<Output = miette::Result<S>> + Send,
+    S: AsyncBufRead + Unpin + Send,
+{
+    type Stream = S;
+    async fn connect(&mut self) -> miette::Result<S> { self().await }
+}
+
+/// Connector that opens a serial port.
+#[derive(Debug, Clone)]
+pub struct SerialConnector {
+    path: String,
+    baud_rate: u32,
+}
+
+impl SerialConnector {
+    pub fn new(path: impl Into<String>, baud_rate: u32) -> Self {
+        Self {
+            path: path.into(),
+            baud_rate,
+        }
+    }
+}
+
+#[async_trait]
+impl IAsyncConnector for SerialConnector {
+    type Stream = BufReader<SerialStream>;
+    async fn connect(&mut self) -> miette::Result<Self::Stream> {
+        let serial = tokio_serial::new(&self.path, self.baud_rate)
+            .open_native_async()
+            .into_diagnostic()?;
+        Ok(BufReader::new(serial))
+    }
+}
+
+/// What a [`ResilientReader`] produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReaderEvent {
+    /// A complete frame.
+    Line(String),
+    /// The stream was (re)opened.
+    Connected,
+    /// The stream failed or reached its end and was dropped.
+    Disconnected { reason: String },
+    /// No frame arrived within the idle timeout; the stream was dropped.
+    IdleTimeout,
+    /// Opening the stream failed; the next attempt starts after `retry_in`.
+    ConnectFailed {
+        attempt: usize,
+        reason: String,
+        retry_in: Duration,
+    },
+}
+
+/// Async reader that survives a flaky source.
+///
+/// The stream is opened through an [`IAsyncConnector`] and reopened with
+/// exponential backoff whenever it fails, ends or stays silent longer than
+/// the idle timeout. [`ResilientReader::next_event`] reports these state
+/// changes next to the lines; the [`AsyncIRaxReader`] methods only return
+/// lines and retry transparently.
+pub struct ResilientReader<C: IAsyncConnector> {
+    connector: C,
+    state: ReconnectState,
+    framing: Framing,
+    reader: Option<AsyncRaxReader<C::Stream>>,
+    /// Counters of the streams already dropped.
+    closed_stats: ReaderStats,
+}
+
+impl<C: IAsyncConnector> ResilientReader<C> {
+    pub fn new(connector: C, policy: ReconnectPolicy) -> Self {
+        Self::with_framing(connector, policy, Framing::default())
+    }
+    pub fn with_framing(connector: C, policy: ReconnectPolicy, framing: Framing) -> Self {
+        Self {
+            connector,
+            state: ReconnectState::new(policy),
+            framing,
+            reader: None,
+            closed_stats: ReaderStats::default(),
+        }
+    }
+    /// Whether a stream is currently open.
+    pub fn is_connected(&self) -> bool { self.reader.is_some() }
+    /// Counters summed over every stream opened so far.
+    pub fn stats(&self) -> ReaderStats {
+        let mut stats = self.closed_stats;
+        if let Some(reader) = &self.reader {
+            stats += reader.stats();
+        }
+        stats
+    }
+
+    /// Wait for the next line or connection state change.
+    ///
+    /// Fails once `max_attempts` connection attempts in a row have failed, or
+    /// with a recoverable [`OversizedFrameError`] or
+    /// [`InvalidUtf8Error`](super::InvalidUtf8Error).
+    pub async fn next_event(&mut self) -> miette::Result<ReaderEvent> {
+        if self.reader.is_none() {
+            return self.connect().await;
+        }
+        match self.poll_frame().await? {
+            Ok(()) => {
+                let reader = self.reader.as_mut().expect("reader is connected");
+                Ok(ReaderEvent::Line(reader.framer.take_string()?))
+            }
+            Err(event) => Ok(event),
+        }
+    }
+
+    async fn connect(&mut self) -> miette::Result<ReaderEvent> {
+        let Some(delay) = self.state.next_attempt() else {
+            miette::bail!(
+                "giving up after {} failed connection attempts",
+                self.state.attempt()
+            );
+        };
+        tokio::time::sleep(delay).await;
+        match self.connector.connect().await {
+            Ok(stream) => {
+                clerk::info!(
+                    "[ResilientReader] connected after {} attempt(s)",
+                    self.state.attempt()
+                );
+                self.reader = Some(AsyncRaxReader::with_framing(stream, self.framing.clone()));
+                Ok(ReaderEvent::Connected)
+            }
+            Err(e) => {
+                let retry_in = self.state.retry_in();
+                clerk::warn!(
+                    "[ResilientReader] connection attempt {} failed: {}, retrying in {:?}",
+                    self.state.attempt(),
+                    e,
+                    retry_in
+                );
+                Ok(ReaderEvent::ConnectFailed {
+                    attempt: self.state.attempt(),
+                    reason: e.to_string(),
+                    retry_in,
+                })
+            }
+        }
+    }
+
+    fn disconnect(&mut self) {
+        if let Some(reader) = self.reader.take() {
+            self.closed_stats += reader.stats();
+        }
+    }
+
+    /// Read the next frame of the open stream. `Ok(Err(event))` means the
+    /// stream was dropped.
+    async fn poll_frame(&mut self) -> miette::Result<Result<(), ReaderEvent>> {
+        let reader = self.reader.as_mut().expect("reader is connected");
+        let result = match self.state.policy.idle_timeout {
+            Some(timeout) => match tokio::time::timeout(timeout, reader.next_frame()).await {
+                Ok(result) => result,
+                Err(_) => {
+                    clerk::warn!("[ResilientReader] idle for {:?}, reconnecting", timeout);
+                    self.disconnect();
+                    return Ok(Err(ReaderEvent::IdleTimeout));
+                }
+            },
+            None => reader.next_frame().await,
+        };
+        let reason = match result {
+            Ok(true) => {
+                self.state.frame_received();
+                return Ok(Ok(()));
+            }
+            Ok(false) => "end of stream".to_string(),
+            Err(e) if e.downcast_ref::<OversizedFrameError>().is_some() => return Err(e),
+            Err(e) => e.to_string(),
+        };
+        clerk::warn!("[ResilientReader] disconnected: {}", reason);
+        self.disconnect();
+        Ok(Err(ReaderEvent::Disconnected { reason }))
+    }
+}
+
+#[async_trait]
+impl<C: IAsyncConnector> AsyncIRaxReader for ResilientReader<C> {
+    /// Waits for the next line, reconnecting as needed. Never returns `None`;
+    /// running out of connection attempts is an error.
+    async fn read_line(&mut self) -> miette::Result<Option<String>> {
+        loop {
+            if let ReaderEvent::Line(line) = self.next_event().await? {
+                return Ok(Some(line));
+            }
+        }
+    }
+
+    async fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>> {
+        let mut lines = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.read_line().await? {
+                Some(line) => lines.push(line),
+                None => break,
+            }
+        }
+        Ok(lines)
+    }
+}
+
+#[async_trait]
+impl<C: IAsyncConnector> AsyncIRaxLendingReader for ResilientReader<C> {
+    async fn read_line_ref(&mut self) -> miette::Result<Option<&str>> {
+        loop {
+            if self.reader.is_none() {
+                self.connect().await?;
+            } else if self.poll_frame().await?.is_ok() {
+                break;
+            }
+        }
+        let reader = self.reader.as_mut().expect("reader is connected");
+        Ok(Some(reader.framer.frame_str()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use clerk::{LogLevel, init_log_with_level};
+    use tokio::io::{AsyncWriteExt, DuplexStream};
+
+    use super::*;
+
+    type Scripted = VecDeque<miette::Result<BufReader<DuplexStream>>>;
+
+    fn stream(data: &'static [u8], keep_open: bool) -> miette::Result<BufReader<DuplexStream>> {
+        let (mut tx, rx) = tokio::io::duplex(64);
+        tokio::spawn(async move {
+            tx.write_all(data).await.unwrap();
+            if keep_open {
+                std::future::pending::<()>().await;
+            }
+        });
+        Ok(BufReader::new(rx))
+    }
+
+    fn scripted(
+        script: Scripted,
+    ) -> impl FnMut() -> std::future::Ready<miette::Result<BufReader<DuplexStream>>> {
+        let script = Arc::new(Mutex::new(script));
+        move || {
+            let next = script.lock().unwrap().pop_front();
+            std::future::ready(next.unwrap_or_else(|| Err(miette::miette!("no more streams"))))
+        }
+    }
+
+    fn policy() -> ReconnectPolicy {
+        ReconnectPolicy {
+            idle_timeout: Some(Duration::from_millis(50)),
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            multiplier: 2,
+            max_attempts: Some(3),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let script: Scripted = VecDeque::from([
+            Err(miette::miette!("port missing")),
+            stream(b"a\nb\n", false),
+            stream(b"c\n", true),
+        ]);
+        let mut reader = ResilientReader::new(scripted(script), policy());
+        let mut events = Vec::new();
+        for _ in 0..8 {
+            events.push(reader.next_event().await?);
+        }
+        assert!(matches!(
+            &events[0],
+            ReaderEvent::ConnectFailed { attempt: 1, retry_in, .. } if *retry_in == Duration::from_millis(1)
+        ));
+        assert_eq!(
+            events[1..],
+            [
+                ReaderEvent::Connected,
+                ReaderEvent::Line("a\n".into()),
+                ReaderEvent::Line("b\n".into()),
+                ReaderEvent::Disconnected {
+                    reason: "end of stream".into()
+                },
+                ReaderEvent::Connected,
+                ReaderEvent::Line("c\n".into()),
+                ReaderEvent::IdleTimeout,
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_gives_up() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = ResilientReader::new(scripted(VecDeque::new()), policy());
+        assert!(reader.read_line().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_backoff_when_stream_ends_at_once() {
+        init_log_with_level(LogLevel::TRACE);
+        let connects = Arc::new(AtomicUsize::new(0));
+        let connector = {
+            let connects = connects.clone();
+            move || {
+                connects.fetch_add(1, Ordering::SeqCst);
+                std::future::ready(stream(b"", false))
+            }
+        };
+        let policy = ReconnectPolicy {
+            initial_backoff: Duration::from_millis(20),
+            max_backoff: Duration::from_millis(40),
+            max_attempts: Some(4),
+            ..policy()
+        };
+        let mut reader = ResilientReader::new(connector, policy);
+        let started = tokio::time::Instant::now();
+        assert!(reader.read_line().await.is_err());
+        // 0 + 20 + 40 + 40 ms between the four attempts.
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert_eq!(connects.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_read_line_skips_events() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let script: Scripted = VecDeque::from([
+            Err(miette::miette!("port missing")),
+            stream(b"a\n", false),
+            stream(b"b\n", false),
+        ]);
+        let mut reader = ResilientReader::new(scripted(script), policy());
+        assert_eq!(reader.read_line().await?, Some("a\n".to_string()));
+        assert_eq!(reader.read_line_ref().await?, Some("b\n"));
+        assert_eq!(reader.stats().frames, 2);
+        Ok(())
+    }
+}
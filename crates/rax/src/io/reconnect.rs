@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+/// When a reconnecting source such as `ResilientReader` gives up on a
+/// connection and how fast it retries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Drop the connection if no frame arrives for this long.
+    pub idle_timeout: Option<Duration>,
+    /// Delay before the second connection attempt; the first one is
+    /// immediate.
+    pub initial_backoff: Duration,
+    /// Upper bound of the delay between attempts.
+    pub max_backoff: Duration,
+    /// Factor the delay grows by after every failed attempt.
+    pub multiplier: u32,
+    /// Give up after this many failed attempts in a row. A connection that
+    /// drops before its first frame counts as failed.
+    pub max_attempts: Option<usize>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Some(Duration::from_secs(5)),
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before connection attempt number `attempt`, counted from 0.
+    pub fn backoff(&self, attempt: usize) -> Duration {
+        if attempt == 0 {
+            return Duration::ZERO;
+        }
+        let factor = self
+            .multiplier
+            .checked_pow(u32::try_from(attempt - 1).unwrap_or(u32::MAX))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |d| d.min(self.max_backoff))
+    }
+}
+
+/// Counts the connection attempts of a reconnecting source against its
+/// [`ReconnectPolicy`]. The count is only reset once a connection delivered
+/// a frame, so a peer that accepts and closes right away is still retried
+/// with backoff.
+#[cfg(any(feature = "async", feature = "net"))]
+#[derive(Debug, Clone)]
+pub(crate) struct ReconnectState {
+    pub policy: ReconnectPolicy,
+    attempt: usize,
+}
+
+#[cfg(any(feature = "async", feature = "net"))]
+impl ReconnectState {
+    pub fn new(policy: ReconnectPolicy) -> Self { Self { policy, attempt: 0 } }
+    /// Number of attempts since the last frame.
+    pub fn attempt(&self) -> usize { self.attempt }
+    /// Count a new attempt and return the delay to wait before it, or `None`
+    /// once `max_attempts` attempts in a row have failed.
+    pub fn next_attempt(&mut self) -> Option<Duration> {
+        if self
+            .policy
+            .max_attempts
+            .is_some_and(|max| self.attempt >= max)
+        {
+            return None;
+        }
+        let delay = self.policy.backoff(self.attempt);
+        self.attempt += 1;
+        Some(delay)
+    }
+    /// Delay before the attempt after the current one.
+    pub fn retry_in(&self) -> Duration { self.policy.backoff(self.attempt) }
+    /// The connection works; the next reconnect starts without delay.
+    pub fn frame_received(&mut self) { self.attempt = 0; }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        let policy = ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+            ..Default::default()
+        };
+        let delays: Vec<_> = (0..6).map(|n| policy.backoff(n).as_millis()).collect();
+        assert_eq!(delays, [0, 100, 200, 400, 800, 1000]);
+        assert_eq!(policy.backoff(200), Duration::from_secs(1));
+    }
+
+    #[cfg(any(feature = "async", feature = "net"))]
+    #[test]
+    fn test_state() {
+        let mut state = ReconnectState::new(ReconnectPolicy {
+            initial_backoff: Duration::from_millis(100),
+            max_attempts: Some(3),
+            ..Default::default()
+        });
+        let delays: Vec<_> = std::iter::from_fn(|| state.next_attempt()).collect();
+        assert_eq!(delays, [0, 100, 200].map(Duration::from_millis));
+        assert_eq!(state.attempt(), 3);
+        state.frame_received();
+        assert_eq!(state.next_attempt(), Some(Duration::ZERO));
+        assert_eq!(state.retry_in(), Duration::from_millis(100));
+    }
+}
@@ -0,0 +1,265 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use miette::IntoDiagnostic;
+
+use super::{CAPTURE_HEADER, CaptureRecord, IRaxLendingReader, IRaxReader};
+
+#[derive(Debug)]
+struct ReplayState {
+    speed: f64,
+    paused: bool,
+    seek: Option<Duration>,
+}
+
+/// Handle for steering a [`ReplayReader`] from another thread.
+#[derive(Debug, Clone)]
+pub struct ReplayControl {
+    shared: Arc<(Mutex<ReplayState>, Condvar)>,
+}
+
+impl ReplayControl {
+    fn new() -> Self {
+        Self {
+            shared: Arc::new((
+                Mutex::new(ReplayState {
+                    speed: 1.0,
+                    paused: false,
+                    seek: None,
+                }),
+                Condvar::new(),
+            )),
+        }
+    }
+    fn state(&self) -> MutexGuard<'_, ReplayState> {
+        self.shared.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+    fn update(&self, f: impl FnOnce(&mut ReplayState)) {
+        f(&mut self.state());
+        self.shared.1.notify_all();
+    }
+
+    /// Replay `speed` times faster than recorded. `f64::INFINITY` replays
+    /// without delays.
+    ///
+    /// # Panics
+    /// If `speed` is not greater than zero.
+    pub fn set_speed(&self, speed: f64) {
+        assert!(speed > 0.0, "replay speed must be greater than zero");
+        self.update(|s| s.speed = speed);
+    }
+    pub fn speed(&self) -> f64 { self.state().speed }
+    /// Block reads until [`resume`](Self::resume) is called.
+    pub fn pause(&self) { self.update(|s| s.paused = true); }
+    pub fn resume(&self) { self.update(|s| s.paused = false); }
+    pub fn is_paused(&self) -> bool { self.state().paused }
+    /// Continue with the first record at or after `offset` from the start of
+    /// the capture.
+    pub fn seek(&self, offset: Duration) { self.update(|s| s.seek = Some(offset)); }
+}
+
+/// Replay clock: capture time `pos` was reached at `at`.
+#[derive(Debug, Clone, Copy)]
+struct Clock {
+    at: Instant,
+    pos: Duration,
+    speed: f64,
+}
+
+/// Reads a capture file written by [`TeeReader`](super::TeeReader) and
+/// returns its lines with the original timing.
+///
+/// The capture is loaded into memory. Reads block until the next line is
+/// due; speed, pause and seek are controlled through [`ReplayControl`].
+pub struct ReplayReader {
+    records: Vec<CaptureRecord>,
+    next: usize,
+    clock: Option<Clock>,
+    control: ReplayControl,
+}
+
+impl ReplayReader {
+    /// Load a capture file.
+    pub fn open(path: impl AsRef<Path>) -> miette::Result<Self> {
+        let file = File::open(path).into_diagnostic()?;
+        Self::from_reader(BufReader::new(file))
+    }
+    /// Load a capture from any buffered source.
+    pub fn from_reader(reader: impl BufRead) -> miette::Result<Self> {
+        let mut lines = reader.lines();
+        match lines.next().transpose().into_diagnostic()? {
+            Some(header) if header.trim_end() == CAPTURE_HEADER => {}
+            header => miette::bail!("not a rax capture, header is {header:?}"),
+        }
+        let records = lines
+            .map(|l| CaptureRecord::decode(&l.into_diagnostic()?))
+            .collect::<miette::Result<Vec<_>>>()?;
+        clerk::debug!("[ReplayReader] loaded {} records", records.len());
+        Ok(Self {
+            records,
+            next: 0,
+            clock: None,
+            control: ReplayControl::new(),
+        })
+    }
+
+    pub fn records(&self) -> &[CaptureRecord] { &self.records }
+    /// A handle to change speed, pause or seek, possibly from another thread.
+    pub fn control(&self) -> ReplayControl { self.control.clone() }
+
+    /// Wait until the next record is due and return its index.
+    fn next_record(&mut self) -> Option<usize> {
+        let (_, cvar) = &*self.control.shared;
+        let mut state = self.control.state();
+        loop {
+            let now = Instant::now();
+            if let Some(target) = state.seek.take() {
+                self.next = self.records.partition_point(|r| r.offset < target);
+                clerk::debug!("[ReplayReader] seek to {:?}, record {}", target, self.next);
+                self.clock = Some(Clock {
+                    at: now,
+                    pos: target,
+                    speed: state.speed,
+                });
+            }
+            if let Some(clock) = &mut self.clock {
+                if clock.speed.is_finite() {
+                    clock.pos += (now - clock.at).mul_f64(clock.speed);
+                }
+                clock.at = now;
+                clock.speed = state.speed;
+            }
+            if state.paused {
+                state = cvar.wait(state).unwrap_or_else(|e| e.into_inner());
+                // Time spent paused does not advance the replay.
+                if let Some(clock) = &mut self.clock {
+                    clock.at = Instant::now();
+                }
+                continue;
+            }
+            let record = self.records.get(self.next)?;
+            let clock = self.clock.get_or_insert(Clock {
+                at: now,
+                pos: record.offset,
+                speed: state.speed,
+            });
+            if clock.pos >= record.offset || state.speed.is_infinite() {
+                clock.pos = clock.pos.max(record.offset);
+                self.next += 1;
+                return Some(self.next - 1);
+            }
+            let wait = (record.offset - clock.pos).div_f64(state.speed);
+            state = cvar
+                .wait_timeout(state, wait)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+}
+
+impl IRaxReader for ReplayReader {
+    fn read_line(&mut self) -> miette::Result<Option<String>> {
+        Ok(self.read_line_ref()?.map(str::to_string))
+    }
+
+    fn read_lines_by_count(&mut self, count: usize) -> miette::Result<Vec<String>> {
+        let mut lines = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.read_line()? {
+                Some(line) => lines.push(line),
+                None => break,
+            }
+        }
+        Ok(lines)
+    }
+}
+
+impl IRaxLendingReader for ReplayReader {
+    fn read_line_ref(&mut self) -> miette::Result<Option<&str>> {
+        let Some(i) = self.next_record() else {
+            return Ok(None);
+        };
+        let line = self.records[i].line.as_str();
+        clerk::debug!("[ReplayReader] read_line: line = {:?}", line);
+        Ok(Some(line))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+    use std::time::UNIX_EPOCH;
+
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+
+    fn capture(offsets_ms: &[u64]) -> ReplayReader {
+        let mut text = format!("{CAPTURE_HEADER}\n");
+        for (i, ms) in offsets_ms.iter().enumerate() {
+            let record = CaptureRecord {
+                offset: Duration::from_millis(*ms),
+                wall: UNIX_EPOCH,
+                line: format!("line{i}\n"),
+            };
+            text.push_str(&record.encode());
+            text.push('\n');
+        }
+        ReplayReader::from_reader(Cursor::new(text)).unwrap()
+    }
+
+    #[test]
+    fn test_rejects_missing_header() {
+        init_log_with_level(LogLevel::TRACE);
+        assert!(ReplayReader::from_reader(Cursor::new("0\t0\tx\n")).is_err());
+    }
+
+    #[test]
+    fn test_timing_and_speed() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = capture(&[1000, 1100, 1200]);
+        reader.control().set_speed(2.0);
+        let started = Instant::now();
+        assert_eq!(reader.read_lines_by_count(5)?.len(), 3);
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(100), "{elapsed:?}");
+        // Only a loose upper bound, CI machines may be slow.
+        assert!(elapsed < Duration::from_secs(1), "{elapsed:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_seek() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = capture(&[0, 10, 20, 30]);
+        reader.control().set_speed(f64::INFINITY);
+        assert_eq!(reader.read_line()?.as_deref(), Some("line0\n"));
+        reader.control().seek(Duration::from_millis(15));
+        assert_eq!(reader.read_line()?.as_deref(), Some("line2\n"));
+        reader.control().seek(Duration::ZERO);
+        assert_eq!(reader.read_line()?.as_deref(), Some("line0\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_pause_resume() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = capture(&[0, 50]);
+        let control = reader.control();
+        assert_eq!(reader.read_line()?.as_deref(), Some("line0\n"));
+        control.pause();
+        let resumer = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(80));
+            control.resume();
+        });
+        let started = Instant::now();
+        assert_eq!(reader.read_line()?.as_deref(), Some("line1\n"));
+        // The pause does not count towards the 50 ms gap.
+        assert!(started.elapsed() >= Duration::from_millis(125));
+        resumer.join().unwrap();
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use futures_util::Stream;
+use tokio::io::AsyncBufRead;
+
+use super::{AsyncIRaxReader, AsyncRaxReader};
+
+/// Turn any `AsyncIRaxReader` into a stream of lines that ends at EOF.
+///
+/// Errors are yielded as items and the stream keeps reading afterwards, so
+/// recoverable errors such as oversized frames can be skipped; stop
+/// consuming on errors that are not.
+pub fn line_stream<R>(reader: R) -> impl Stream<Item = miette::Result<String>> + Send
+where
+    R: AsyncIRaxReader + Send + 'static,
+{
+    futures_util::stream::unfold(reader, |mut reader| async move {
+        match reader.read_line().await {
+            Ok(Some(line)) => Some((Ok(line), reader)),
+            Ok(None) => None,
+            Err(e) => Some((Err(e), reader)),
+        }
+    })
+}
+
+impl<R> AsyncRaxReader<R>
+where
+    R: AsyncBufRead + Unpin + Send + 'static,
+{
+    /// Consume the reader and return its lines as a stream, see
+    /// [`line_stream`].
+    pub fn into_stream(self) -> impl Stream<Item = miette::Result<String>> + Send {
+        line_stream(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+    use futures_util::{StreamExt, TryStreamExt};
+    use tokio::io::BufReader;
+
+    use super::*;
+    use crate::io::Framing;
+
+    #[tokio::test]
+    async fn test_into_stream() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let reader = AsyncRaxReader::new(BufReader::new("a\nb\nc".as_bytes()));
+        let lines: Vec<_> = reader.into_stream().try_collect().await?;
+        assert_eq!(lines, ["a\n", "b\n", "c"]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stream_continues_after_error() {
+        init_log_with_level(LogLevel::TRACE);
+        let reader = AsyncRaxReader::with_framing(
+            BufReader::new("ok\ntoo long\nend\n".as_bytes()),
+            Framing::lines().max_len(4),
+        );
+        let items: Vec<_> = reader.into_stream().collect().await;
+        assert_eq!(items.len(), 3);
+        assert!(items[1].is_err());
+        assert_eq!(items[2].as_deref().ok(), Some("end\n"));
+    }
+}
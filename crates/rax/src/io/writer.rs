@@ -0,0 +1,161 @@
+use std::io::Write;
+use std::time::{Duration, Instant};
+
+use miette::IntoDiagnostic;
+
+use super::IRaxReader;
+
+/// XOR checksum of a sentence body, skipping a leading `$` or `!`.
+pub fn checksum(sentence: &str) -> u8 {
+    sentence
+        .strip_prefix(['$', '!'])
+        .unwrap_or(sentence)
+        .bytes()
+        .fold(0, |acc, b| acc ^ b)
+}
+
+/// Append `*hh\r\n` to `sentence` in `buf`.
+pub(crate) fn frame_sentence(buf: &mut String, sentence: &str) -> miette::Result<()> {
+    if sentence.contains(['*', '\r', '\n']) {
+        miette::bail!("sentence must not contain a checksum or line break: {sentence:?}");
+    }
+    buf.clear();
+    buf.push_str(sentence);
+    buf.push_str(&format!("*{:02X}\r\n", checksum(sentence)));
+    Ok(())
+}
+
+/// Trait for writing lines to a sink, e.g. commands to a receiver.
+pub trait IRaxWriter {
+    /// Writes `line` followed by `\r\n`.
+    fn write_line(&mut self, line: &str) -> miette::Result<()>;
+    /// Writes `sentence` with a `*hh\r\n` checksum appended.
+    fn write_sentence(&mut self, sentence: &str) -> miette::Result<()>;
+    fn flush(&mut self) -> miette::Result<()>;
+
+    /// Sends `sentence` and reads from `reader` until a line satisfies
+    /// `is_reply` or `timeout` has passed. Lines that do not match are
+    /// dropped.
+    ///
+    /// The timeout is checked between lines, so a blocking reader needs a
+    /// read timeout of its own.
+    fn request<R, F>(
+        &mut self,
+        reader: &mut R,
+        sentence: &str,
+        timeout: Duration,
+        mut is_reply: F,
+    ) -> miette::Result<String>
+    where
+        Self: Sized,
+        R: IRaxReader + ?Sized,
+        F: FnMut(&str) -> bool,
+    {
+        self.write_sentence(sentence)?;
+        self.flush()?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            match reader.read_line()? {
+                Some(line) if is_reply(&line) => return Ok(line),
+                Some(line) => clerk::trace!("[IRaxWriter] request: skipping {:?}", line),
+                None => {
+                    miette::bail!("reader reached EOF while waiting for a reply to {sentence:?}")
+                }
+            }
+            if Instant::now() >= deadline {
+                miette::bail!("no reply to {sentence:?} within {timeout:?}");
+            }
+        }
+    }
+}
+
+/// A line writer over any `std::io::Write` that implements `IRaxWriter`.
+pub struct RaxWriter<W: Write> {
+    inner: W,
+    buf: String,
+}
+
+impl<W: Write> RaxWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buf: String::new(),
+        }
+    }
+    /// Consume the writer and return the inner sink.
+    pub fn into_inner(self) -> W { self.inner }
+}
+
+impl<W: Write> IRaxWriter for RaxWriter<W> {
+    fn write_line(&mut self, line: &str) -> miette::Result<()> {
+        clerk::debug!("[RaxWriter] write_line: {:?}", line);
+        self.inner.write_all(line.as_bytes()).into_diagnostic()?;
+        self.inner.write_all(b"\r\n").into_diagnostic()
+    }
+    fn write_sentence(&mut self, sentence: &str) -> miette::Result<()> {
+        frame_sentence(&mut self.buf, sentence)?;
+        clerk::debug!("[RaxWriter] write_sentence: {:?}", self.buf);
+        self.inner.write_all(self.buf.as_bytes()).into_diagnostic()
+    }
+    fn flush(&mut self) -> miette::Result<()> { self.inner.flush().into_diagnostic() }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::io::RaxReader;
+
+    #[test]
+    fn test_checksum() {
+        init_log_with_level(LogLevel::TRACE);
+        assert_eq!(checksum("$EIGPQ,RMC"), 0x3A);
+        assert_eq!(checksum("EIGPQ,RMC"), 0x3A);
+        assert_eq!(checksum("!AIVDM"), checksum("AIVDM"));
+    }
+
+    #[test]
+    fn test_write_line_and_sentence() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut writer = RaxWriter::new(Vec::new());
+        writer.write_line("hello")?;
+        writer.write_sentence("$EIGPQ,RMC")?;
+        writer.flush()?;
+        assert_eq!(writer.into_inner(), b"hello\r\n$EIGPQ,RMC*3A\r\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_sentence_rejects_checksum() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut writer = RaxWriter::new(Vec::new());
+        assert!(writer.write_sentence("$EIGPQ,RMC*3A").is_err());
+    }
+
+    #[test]
+    fn test_request() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = RaxReader::new(Cursor::new("$GPGGA,1\n$GPRMC,2\n"));
+        let mut writer = RaxWriter::new(Vec::new());
+        let reply = writer.request(&mut reader, "$EIGPQ,RMC", Duration::from_secs(1), |l| {
+            l.starts_with("$GPRMC")
+        })?;
+        assert_eq!(reply, "$GPRMC,2\n");
+        assert_eq!(writer.into_inner(), b"$EIGPQ,RMC*3A\r\n");
+        Ok(())
+    }
+
+    #[test]
+    fn test_request_eof() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut reader = RaxReader::new(Cursor::new("$GPGGA,1\n"));
+        let mut writer = RaxWriter::new(Vec::new());
+        let result = writer.request(&mut reader, "$EIGPQ,RMC", Duration::from_secs(1), |l| {
+            l.starts_with("$GPRMC")
+        });
+        assert!(result.is_err());
+    }
+}
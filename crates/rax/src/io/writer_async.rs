@@ -0,0 +1,147 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use miette::IntoDiagnostic;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use super::{AsyncIRaxReader, frame_sentence};
+
+/// Async counterpart of `IRaxWriter`.
+#[async_trait]
+pub trait AsyncIRaxWriter: Send {
+    /// Writes `line` followed by `\r\n`.
+    async fn write_line(&mut self, line: &str) -> miette::Result<()>;
+    /// Writes `sentence` with a `*hh\r\n` checksum appended.
+    async fn write_sentence(&mut self, sentence: &str) -> miette::Result<()>;
+    async fn flush(&mut self) -> miette::Result<()>;
+
+    /// Sends `sentence` and reads from `reader` until a line satisfies
+    /// `is_reply`, failing after `timeout`. Lines that do not match are
+    /// dropped.
+    async fn request<R, F>(
+        &mut self,
+        reader: &mut R,
+        sentence: &str,
+        timeout: Duration,
+        mut is_reply: F,
+    ) -> miette::Result<String>
+    where
+        Self: Sized,
+        R: AsyncIRaxReader + Send + ?Sized,
+        F: FnMut(&str) -> bool + Send,
+    {
+        self.write_sentence(sentence).await?;
+        self.flush().await?;
+        let wait = async {
+            loop {
+                match reader.read_line().await? {
+                    Some(line) if is_reply(&line) => return Ok(line),
+                    Some(line) => {
+                        clerk::trace!("[AsyncIRaxWriter] request: skipping {:?}", line)
+                    }
+                    None => miette::bail!(
+                        "reader reached EOF while waiting for a reply to {sentence:?}"
+                    ),
+                }
+            }
+        };
+        match tokio::time::timeout(timeout, wait).await {
+            Ok(reply) => reply,
+            Err(_) => miette::bail!("no reply to {sentence:?} within {timeout:?}"),
+        }
+    }
+}
+
+/// Async line writer implementing `AsyncIRaxWriter`.
+pub struct AsyncRaxWriter<W: AsyncWrite + Unpin + Send> {
+    inner: W,
+    buf: String,
+}
+
+impl<W: AsyncWrite + Unpin + Send> AsyncRaxWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            buf: String::new(),
+        }
+    }
+    /// Consume the writer and return the inner sink.
+    pub fn into_inner(self) -> W { self.inner }
+}
+
+#[async_trait]
+impl<W: AsyncWrite + Unpin + Send> AsyncIRaxWriter for AsyncRaxWriter<W> {
+    async fn write_line(&mut self, line: &str) -> miette::Result<()> {
+        clerk::debug!("[AsyncRaxWriter] write_line: {:?}", line);
+        self.inner
+            .write_all(line.as_bytes())
+            .await
+            .into_diagnostic()?;
+        self.inner.write_all(b"\r\n").await.into_diagnostic()
+    }
+    async fn write_sentence(&mut self, sentence: &str) -> miette::Result<()> {
+        frame_sentence(&mut self.buf, sentence)?;
+        clerk::debug!("[AsyncRaxWriter] write_sentence: {:?}", self.buf);
+        self.inner
+            .write_all(self.buf.as_bytes())
+            .await
+            .into_diagnostic()
+    }
+    async fn flush(&mut self) -> miette::Result<()> { self.inner.flush().await.into_diagnostic() }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+    use tokio::io::{AsyncWriteExt, BufReader};
+
+    use super::*;
+    use crate::io::AsyncRaxReader;
+
+    #[tokio::test]
+    async fn test_write_sentence() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut writer = AsyncRaxWriter::new(Vec::new());
+        writer.write_line("hello").await?;
+        writer.write_sentence("$EIGPQ,RMC").await?;
+        writer.flush().await?;
+        assert_eq!(writer.into_inner(), b"hello\r\n$EIGPQ,RMC*3A\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_request() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let (client, mut device) = tokio::io::duplex(256);
+        let (rx, tx) = tokio::io::split(client);
+        let mut reader = AsyncRaxReader::new(BufReader::new(rx));
+        let mut writer = AsyncRaxWriter::new(tx);
+        device
+            .write_all(b"$GPGGA,1\r\n$GPRMC,2\r\n")
+            .await
+            .into_diagnostic()?;
+        let reply = writer
+            .request(&mut reader, "$EIGPQ,RMC", Duration::from_secs(1), |l| {
+                l.starts_with("$GPRMC")
+            })
+            .await?;
+        assert_eq!(reply, "$GPRMC,2\r\n");
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let (client, _device) = tokio::io::duplex(256);
+        let (rx, tx) = tokio::io::split(client);
+        let mut reader = AsyncRaxReader::new(BufReader::new(rx));
+        let mut writer = AsyncRaxWriter::new(tx);
+        let result = writer
+            .request(&mut reader, "$EIGPQ,RMC", Duration::from_millis(20), |_| {
+                true
+            })
+            .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+}
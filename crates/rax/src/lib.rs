@@ -2,4 +2,6 @@
 pub mod device;
 
 pub mod io;
+#[cfg(all(feature = "pty", unix))]
+pub mod pty;
 pub mod str_parser;
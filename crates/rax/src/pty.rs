@@ -0,0 +1,251 @@
+//! Pseudo-terminal backed virtual serial device.
+//!
+//! [`VirtualSerial`] opens a pty pair and exposes the slave side as a path
+//! that `serialport` and `tokio-serial` can open like a real port. The
+//! master side plays the device: data passed to [`VirtualSerial::send`]
+//! arrives at the port, and everything written to the port is collected and
+//! can be inspected with [`VirtualSerial::received`].
+
+use std::fs::File;
+use std::io::{Read, Write};
+use std::os::fd::{AsFd, OwnedFd};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use miette::IntoDiagnostic;
+use nix::poll::{PollFd, PollFlags, PollTimeout, poll};
+use nix::sys::termios::{SetArg, cfmakeraw, tcgetattr, tcsetattr};
+
+use crate::io::frame_sentence;
+
+#[derive(Debug, Default)]
+struct Received {
+    bytes: Mutex<Vec<u8>>,
+    changed: Condvar,
+}
+
+/// A virtual serial device backed by a pseudo-terminal.
+///
+/// The device stays available until it is dropped. Clients may open and
+/// close the port any number of times in between.
+pub struct VirtualSerial {
+    master: File,
+    path: String,
+    /// Held open so the pty survives clients closing the port.
+    _slave: OwnedFd,
+    received: Arc<Received>,
+    stop: Arc<AtomicBool>,
+    collector: Option<JoinHandle<()>>,
+}
+
+impl VirtualSerial {
+    /// Create a new virtual device.
+    pub fn open() -> miette::Result<Self> {
+        let pty = nix::pty::openpty(None, None).into_diagnostic()?;
+        // Pass bytes through untouched until a client configures the port.
+        let mut termios = tcgetattr(&pty.slave).into_diagnostic()?;
+        cfmakeraw(&mut termios);
+        tcsetattr(&pty.slave, SetArg::TCSANOW, &termios).into_diagnostic()?;
+        let path = nix::unistd::ttyname(&pty.slave)
+            .into_diagnostic()?
+            .to_string_lossy()
+            .into_owned();
+        let master = File::from(pty.master);
+        let received = Arc::new(Received::default());
+        let stop = Arc::new(AtomicBool::new(false));
+        let collector = {
+            let mut master = master.try_clone().into_diagnostic()?;
+            let received = received.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || collect(&mut master, &received, &stop))
+        };
+        clerk::debug!("[VirtualSerial] opened {}", path);
+        Ok(Self {
+            master,
+            path,
+            _slave: pty.slave,
+            received,
+            stop,
+            collector: Some(collector),
+        })
+    }
+
+    /// Path of the port, e.g. `/dev/pts/3`.
+    pub fn path(&self) -> &str { &self.path }
+
+    /// Send raw bytes from the device to the port.
+    pub fn send(&self, bytes: &[u8]) -> miette::Result<()> {
+        clerk::trace!("[VirtualSerial] send: {:?}", String::from_utf8_lossy(bytes));
+        (&self.master).write_all(bytes).into_diagnostic()
+    }
+    /// Send `line` followed by `\r\n`.
+    pub fn send_line(&self, line: &str) -> miette::Result<()> {
+        self.send(format!("{line}\r\n").as_bytes())
+    }
+    /// Send `sentence` with a `*hh\r\n` checksum appended.
+    pub fn send_sentence(&self, sentence: &str) -> miette::Result<()> {
+        let mut buf = String::new();
+        frame_sentence(&mut buf, sentence)?;
+        self.send(buf.as_bytes())
+    }
+
+    /// Everything written to the port so far.
+    pub fn received(&self) -> Vec<u8> { self.bytes().clone() }
+    /// Take everything written to the port so far, clearing the buffer.
+    pub fn take_received(&self) -> Vec<u8> { std::mem::take(&mut *self.bytes()) }
+    /// Wait until the bytes written to the port satisfy `done`, or fail after
+    /// `timeout`.
+    pub fn wait_received(
+        &self,
+        timeout: Duration,
+        mut done: impl FnMut(&[u8]) -> bool,
+    ) -> miette::Result<Vec<u8>> {
+        let deadline = Instant::now() + timeout;
+        let mut bytes = self.bytes();
+        loop {
+            if done(&bytes) {
+                return Ok(bytes.clone());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                miette::bail!(
+                    "timed out waiting for data written to {}, got {:?}",
+                    self.path,
+                    String::from_utf8_lossy(&bytes)
+                );
+            }
+            bytes = self
+                .received
+                .changed
+                .wait_timeout(bytes, deadline - now)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    fn bytes(&self) -> MutexGuard<'_, Vec<u8>> {
+        self.received
+            .bytes
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for VirtualSerial {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(collector) = self.collector.take() {
+            let _ = collector.join();
+        }
+        clerk::debug!("[VirtualSerial] closed {}", self.path);
+    }
+}
+
+/// Copy everything written to the port into `received` until `stop` is set.
+fn collect(master: &mut File, received: &Received, stop: &AtomicBool) {
+    let mut buf = [0u8; 1024];
+    while !stop.load(Ordering::Relaxed) {
+        let mut fds = [PollFd::new(master.as_fd(), PollFlags::POLLIN)];
+        match poll(&mut fds, PollTimeout::from(20u16)) {
+            Ok(0) => continue,
+            Ok(_) => {}
+            Err(nix::errno::Errno::EINTR) => continue,
+            Err(e) => {
+                clerk::warn!("[VirtualSerial] poll failed: {}", e);
+                return;
+            }
+        }
+        match master.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => {
+                let mut bytes = received.bytes.lock().unwrap_or_else(|e| e.into_inner());
+                bytes.extend_from_slice(&buf[..n]);
+                received.changed.notify_all();
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            // EIO is reported while no client has the port open.
+            Err(e) if e.raw_os_error() == Some(nix::libc::EIO) => {
+                std::thread::sleep(Duration::from_millis(20));
+            }
+            Err(e) => {
+                clerk::warn!("[VirtualSerial] read failed: {}", e);
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::io::{IRaxReader, IRaxWriter, RaxReader, RaxWriter};
+
+    fn open_port(device: &VirtualSerial) -> miette::Result<Box<dyn serialport::SerialPort>> {
+        serialport::new(device.path(), 9600)
+            .timeout(Duration::from_secs(2))
+            .open()
+            .into_diagnostic()
+    }
+
+    #[test]
+    fn test_device_to_port() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let device = VirtualSerial::open()?;
+        let port = open_port(&device)?;
+        device.send_sentence("$GPGGA,1")?;
+        device.send_line("$GPRMC,2*00")?;
+        let mut reader = RaxReader::new(BufReader::new(port));
+        assert_eq!(reader.read_line()?.as_deref(), Some("$GPGGA,1*4B\r\n"));
+        assert_eq!(reader.read_line()?.as_deref(), Some("$GPRMC,2*00\r\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_port_to_device() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let device = VirtualSerial::open()?;
+        let mut writer = RaxWriter::new(open_port(&device)?);
+        writer.write_sentence("$EIGPQ,RMC")?;
+        writer.flush()?;
+        let received = device.wait_received(Duration::from_secs(2), |b| b.ends_with(b"\n"))?;
+        assert_eq!(received, b"$EIGPQ,RMC*3A\r\n");
+        assert_eq!(device.take_received(), received);
+        assert!(device.received().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_reopen() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let device = VirtualSerial::open()?;
+        drop(open_port(&device)?);
+        let port = open_port(&device)?;
+        device.send_line("again")?;
+        let mut reader = RaxReader::new(BufReader::new(port));
+        assert_eq!(reader.read_line()?.as_deref(), Some("again\r\n"));
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn test_resilient_reader() -> miette::Result<()> {
+        use crate::io::{AsyncIRaxReader, ReconnectPolicy, ResilientReader, SerialConnector};
+
+        init_log_with_level(LogLevel::TRACE);
+        let device = VirtualSerial::open()?;
+        let connector = SerialConnector::new(device.path(), 9600);
+        let mut reader = ResilientReader::new(connector, ReconnectPolicy::default());
+        device.send_sentence("$GPGGA,1")?;
+        assert_eq!(
+            reader.read_line().await?.as_deref(),
+            Some("$GPGGA,1*4B\r\n")
+        );
+        Ok(())
+    }
+}
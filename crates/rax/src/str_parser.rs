@@ -1,13 +1,58 @@
+use std::str::FromStr;
+use std::time::Instant;
+
+pub mod combinators;
 pub mod filters;
 pub mod rules;
 
+mod bytes_parser_context;
+pub use bytes_parser_context::*;
+mod error;
+pub use error::*;
+mod stream_context;
+pub use stream_context::*;
+
 mod parse_opt;
 pub use parse_opt::*;
-pub use rules::{IRule, IStrFlowRule, IStrGlobalRule};
+mod recorder;
+pub use recorder::*;
+pub use rules::{
+    IBytesFlowRule, IBytesGlobalRule, IRule, IStrFlowRule, IStrGlobalRule, IStrStreamRule, NumMode,
+    Partial,
+};
+
+/// A saved cursor position of a [`StrParserContext`].
+///
+/// Created by [`StrParserContext::checkpoint`] and restored with
+/// [`StrParserContext::rewind`]. A checkpoint is only valid for the input it
+/// was taken on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pos: usize,
+    generation: usize,
+}
+
+/// Line, column and field index of a cursor position.
+///
+/// All values are zero-based. `column` counts bytes from the start of the
+/// line and `field` counts the `,` separators passed on that line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+    pub field: usize,
+}
 
 pub struct StrParserContext {
     full: String,
-    rest: *const str,
+    pos: usize,
+    generation: usize,
+    /// Line number of the first line of `full`, non-zero for contexts created
+    /// by [`StrParserContext::lines`].
+    line_base: usize,
+    /// How `take_field` and `parse_field` handle a malformed field.
+    field_mode: NumMode,
+    recorder: Option<Recorder>,
 }
 
 impl Default for StrParserContext {
@@ -18,66 +63,276 @@ impl StrParserContext {
     pub fn new() -> Self {
         Self {
             full: String::new(),
-            rest: "",
+            pos: 0,
+            generation: 0,
+            line_base: 0,
+            field_mode: NumMode::Lenient,
+            recorder: None,
         }
     }
     pub fn init(&mut self, input: String) -> &mut Self {
+        self.start_input();
         self.full = input;
-        self.rest = self.full.as_str();
         self
     }
+    /// Like `init`, but copies `input` into the existing allocation instead
+    /// of taking ownership of a new `String`.
+    pub fn init_from_str(&mut self, input: &str) -> &mut Self {
+        self.start_input();
+        self.full.clear();
+        self.full.push_str(input);
+        self
+    }
+    fn start_input(&mut self) {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.close_all(self.pos);
+        }
+        self.pos = 0;
+        self.line_base = 0;
+        self.generation = self.generation.wrapping_add(1);
+    }
 
     pub fn full_str(&self) -> &str { self.full.as_str() }
-    pub fn rest_str(&self) -> &str { unsafe { &*self.rest } }
+    pub fn rest_str(&self) -> &str { &self.full[self.pos..] }
+    /// Byte offset of the cursor into `full_str()`.
+    pub fn offset(&self) -> usize { self.pos }
+    /// Line, column and field index of the cursor.
+    pub fn position(&self) -> Position { self.position_at(self.pos) }
+    fn position_at(&self, pos: usize) -> Position {
+        let before = &self.full[..pos];
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        Position {
+            line: self.line_base + before.matches('\n').count(),
+            column: pos - line_start,
+            field: before[line_start..].matches(',').count(),
+        }
+    }
+    /// Iterate over the lines of the full string, each as its own context
+    /// with a fresh cursor. Line endings (`\n` or `\r\n`) are stripped, and
+    /// positions and errors of each sub-context report the line it came from.
+    pub fn lines(&self) -> impl Iterator<Item = StrParserContext> + '_ {
+        self.full
+            .lines()
+            .enumerate()
+            .map(|(i, line)| StrParserContext {
+                full: line.to_string(),
+                pos: 0,
+                generation: 0,
+                line_base: self.line_base + i,
+                field_mode: self.field_mode,
+                recorder: None,
+            })
+    }
     pub fn reset(&mut self) -> &mut Self {
-        self.rest = self.full.as_str();
+        self.pos = 0;
         self
     }
+
+    /// Save the current cursor position.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            pos: self.pos,
+            generation: self.generation,
+        }
+    }
+    /// Move the cursor back to a position saved by `checkpoint()`.
+    /// Fails if the context was re-initialized since.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) -> miette::Result<&mut Self> {
+        if checkpoint.generation != self.generation {
+            miette::bail!("checkpoint was taken on a previous input");
+        }
+        clerk::trace!("rewind: {} -> {}", self.pos, checkpoint.pos);
+        self.pos = checkpoint.pos;
+        Ok(self)
+    }
+
+    /// Apply `rule` at `pos` and return its output with the new position.
+    /// Rules always return a suffix of their input, so the new position is
+    /// derived from the length of that suffix.
+    fn apply_at<'a, R>(
+        full: &'a str,
+        pos: usize,
+        rule: &R,
+        recorder: Option<&mut Recorder>,
+    ) -> (Option<R::Output>, usize)
+    where
+        R: IStrFlowRule<'a>,
+    {
+        let Some(recorder) = recorder else {
+            let (out, rest) = rule.apply(&full[pos..]);
+            return (out, full.len() - rest.len());
+        };
+        let started = Instant::now();
+        let (out, rest) = rule.apply(&full[pos..]);
+        let end = full.len() - rest.len();
+        recorder.leaf(rule.name(), pos, end, out.is_some(), started.elapsed());
+        (out, end)
+    }
 }
 
-impl<'a> StrParserContext {
-    pub fn take<R>(&mut self, rule: &R) -> Option<R::Output>
+impl StrParserContext {
+    /// Start recording every rule applied through `take`, `take_strict`,
+    /// `take_field`, `parse_field`, `try_take` and `skip`. `peek` is not
+    /// recorded.
+    pub fn enable_recorder(&mut self) -> &mut Self {
+        self.recorder.get_or_insert_with(Recorder::new);
+        self
+    }
+    pub fn recorder(&self) -> Option<&Recorder> { self.recorder.as_ref() }
+    /// Stop recording and return what was captured. Open scopes are closed
+    /// as failed.
+    pub fn take_recorder(&mut self) -> Option<Recorder> {
+        let mut recorder = self.recorder.take()?;
+        recorder.close_all(self.pos);
+        Some(recorder)
+    }
+    /// Open a named scope; rules applied until the matching `exit` are
+    /// recorded as its children. No-op without a recorder.
+    pub fn enter(&mut self, name: &str) -> &mut Self {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.enter(name, self.pos);
+        }
+        self
+    }
+    /// Close the innermost scope as successful.
+    pub fn exit(&mut self) -> &mut Self {
+        if let Some(recorder) = &mut self.recorder {
+            recorder.exit(self.pos, true);
+        }
+        self
+    }
+}
+
+impl StrParserContext {
+    pub fn take<'a, R>(&'a mut self, rule: &R) -> Option<R::Output>
     where
         R: IStrFlowRule<'a>,
     {
-        match rule.apply(unsafe { &*self.rest }) {
-            (Some(result), rest) => {
-                self.rest = rest;
-                Some(result)
+        let (out, pos) = Self::apply_at(&self.full, self.pos, rule, self.recorder.as_mut());
+        self.pos = pos;
+        out
+    }
+    pub fn take_strict<'a, R>(&'a mut self, rule: &R) -> miette::Result<R::Output>
+    where
+        R: IStrFlowRule<'a>,
+    {
+        let offset = self.pos;
+        let (out, pos) = Self::apply_at(&self.full, offset, rule, self.recorder.as_mut());
+        self.pos = pos;
+        match out {
+            Some(s) => Ok(s),
+            None => {
+                clerk::debug!("take_strict: rule `{}` failed at {}", rule.name(), offset);
+                Err(self.error_at(offset, rule.name()).into())
             }
-            (None, rest) => {
-                self.rest = rest;
-                None
+        }
+    }
+    /// How `take_field` and `parse_field` handle a non-empty field that does
+    /// not parse. `Lenient`, the default, logs the error and yields `None`;
+    /// `Strict` returns it. Kept across `init`.
+    pub fn field_mode(&mut self, mode: NumMode) -> &mut Self {
+        self.field_mode = mode;
+        self
+    }
+    /// Take an optional field with `rule`. An empty field, or the end of
+    /// the input, gives `Ok(None)`; a non-empty field the rule rejects gives
+    /// a [`StrParserError`] at the field's offset, see
+    /// [`field_mode`](Self::field_mode).
+    pub fn take_field<'a, R>(&'a mut self, rule: &R) -> miette::Result<Option<R::Output>>
+    where
+        R: IStrFlowRule<'a>,
+    {
+        let offset = self.pos;
+        let empty = self.full[offset..].is_empty()
+            || self.full[offset..].starts_with([',', '*', '\r', '\n']);
+        let (out, pos) = Self::apply_at(&self.full, offset, rule, self.recorder.as_mut());
+        self.pos = pos;
+        match out {
+            Some(out) => Ok(Some(out)),
+            None if empty => Ok(None),
+            None => self.malformed_field(offset, rule.name()),
+        }
+    }
+    /// Take the text of an optional field with `until` and parse it with
+    /// `FromStr`. An empty or missing field gives `Ok(None)`; text that does
+    /// not parse gives a [`StrParserError`] at the field's offset, naming the
+    /// parse step, e.g. `parse::<u8>`, see [`field_mode`](Self::field_mode).
+    pub fn parse_field<'a, R, T>(&'a mut self, until: &R) -> miette::Result<Option<T>>
+    where
+        R: IStrFlowRule<'a, Output = &'a str>,
+        T: FromStr,
+    {
+        let offset = self.pos;
+        let (out, pos) = Self::apply_at(&self.full, offset, until, self.recorder.as_mut());
+        self.pos = pos;
+        match out {
+            None | Some("") => Ok(None),
+            Some(text) => match text.parse() {
+                Ok(value) => Ok(Some(value)),
+                Err(_) => self.malformed_field(offset, &parse_rule_name::<T>()),
+            },
+        }
+    }
+    fn malformed_field<T>(&self, offset: usize, rule: &str) -> miette::Result<Option<T>> {
+        let err = self.error_at(offset, rule);
+        match self.field_mode {
+            NumMode::Strict => Err(err.into()),
+            NumMode::Lenient => {
+                clerk::warn!("{}", err);
+                Ok(None)
             }
         }
     }
-    pub fn take_strict<R>(&mut self, rule: &R) -> miette::Result<R::Output>
+    /// A [`StrParserError`] for `rule` failing at byte `offset`, on the line
+    /// the offset belongs to.
+    pub fn error_at(&self, offset: usize, rule: &str) -> StrParserError {
+        let line = self.position_at(offset).line;
+        StrParserError::new(&self.full, offset, rule).with_line(line)
+    }
+    /// Like `take`, but leaves the cursor untouched if the rule fails.
+    pub fn try_take<'a, R>(&'a mut self, rule: &R) -> Option<R::Output>
     where
         R: IStrFlowRule<'a>,
     {
-        match self.take(rule) {
-            Some(s) => Ok(s),
-            None => miette::bail!("take fail"),
+        let (out, pos) = Self::apply_at(&self.full, self.pos, rule, self.recorder.as_mut());
+        if out.is_some() {
+            self.pos = pos;
         }
+        out
+    }
+    /// Apply `rule` at the cursor without consuming any input.
+    pub fn peek<'a, R>(&'a self, rule: &R) -> Option<R::Output>
+    where
+        R: IStrFlowRule<'a>,
+    {
+        Self::apply_at(&self.full, self.pos, rule, None).0
     }
 }
 
-impl<'a> StrParserContext {
+impl StrParserContext {
     pub fn skip<R>(&mut self, rule: &R) -> &mut Self
     where
-        R: IStrFlowRule<'a>,
+        R: for<'a> IStrFlowRule<'a>,
     {
-        self.take(rule);
+        self.pos = Self::apply_at(&self.full, self.pos, rule, self.recorder.as_mut()).1;
         self
     }
     pub fn skip_strict<R>(&mut self, rule: &R) -> miette::Result<&mut Self>
     where
-        R: IStrFlowRule<'a>,
+        R: for<'a> IStrFlowRule<'a>,
     {
         self.take_strict(rule)?;
         Ok(self)
     }
 }
+
+/// Name reported when parsing a field as `T` fails, e.g. `parse::<u8>`.
+pub fn parse_rule_name<T>() -> String {
+    let name = std::any::type_name::<T>();
+    let name = name.rsplit("::").next().unwrap_or(name);
+    format!("parse::<{name}>")
+}
+
 impl<'a> StrParserContext {
     pub fn global<R>(&'a mut self, rule: &R) -> R::Output
     where
@@ -86,3 +341,199 @@ impl<'a> StrParserContext {
         rule.apply(&self.full)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::rules::{Char, UntilChar, UntilMode};
+
+    const UNTIL_COMMA: UntilChar<','> = UntilChar {
+        mode: UntilMode::Discard,
+    };
+
+    #[test]
+    fn test_checkpoint_rewind() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("A,3,05,07".to_string());
+        let cp = ctx.checkpoint();
+        assert_eq!(ctx.take(&UNTIL_COMMA), Some("A"));
+        assert_eq!(ctx.take(&UNTIL_COMMA), Some("3"));
+        assert_eq!(ctx.offset(), 4);
+        ctx.rewind(cp)?;
+        assert_eq!(ctx.offset(), 0);
+        assert_eq!(ctx.rest_str(), "A,3,05,07");
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewind_stale_checkpoint() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("abc".to_string());
+        let cp = ctx.checkpoint();
+        ctx.init("def".to_string());
+        assert!(ctx.rewind(cp).is_err());
+    }
+
+    #[test]
+    fn test_peek() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("A,3".to_string());
+        assert_eq!(ctx.peek(&Char::<'A'>), Some('A'));
+        assert_eq!(ctx.peek(&Char::<'B'>), None);
+        assert_eq!(ctx.rest_str(), "A,3");
+    }
+
+    #[test]
+    fn test_try_take() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("abc".to_string());
+        assert_eq!(ctx.try_take(&UNTIL_COMMA), None);
+        assert_eq!(ctx.rest_str(), "abc");
+        assert_eq!(ctx.try_take(&Char::<'a'>), Some('a'));
+        assert_eq!(ctx.rest_str(), "bc");
+    }
+
+    #[test]
+    fn test_skip_and_reset() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("a,b,c".to_string());
+        ctx.skip(&UNTIL_COMMA).skip(&UNTIL_COMMA);
+        assert_eq!(ctx.rest_str(), "c");
+        ctx.reset();
+        assert_eq!(ctx.rest_str(), "a,b,c");
+    }
+
+    #[test]
+    fn test_init_from_str_reuses_allocation() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init(String::with_capacity(64)).init_from_str("a,b");
+        let cp = ctx.checkpoint();
+        let ptr = ctx.full_str().as_ptr();
+        ctx.skip(&UNTIL_COMMA);
+        ctx.init_from_str("c,d");
+        assert_eq!(ctx.full_str().as_ptr(), ptr);
+        assert_eq!(ctx.rest_str(), "c,d");
+        assert!(ctx.rewind(cp).is_err());
+    }
+
+    #[test]
+    fn test_position() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("a,bc,d\r\ne,f".to_string());
+        assert_eq!(
+            ctx.position(),
+            Position {
+                line: 0,
+                column: 0,
+                field: 0
+            }
+        );
+        ctx.skip(&UNTIL_COMMA).skip(&Char::<'b'>);
+        assert_eq!(
+            ctx.position(),
+            Position {
+                line: 0,
+                column: 3,
+                field: 1
+            }
+        );
+        ctx.skip(&UNTIL_COMMA).skip(&UNTIL_COMMA).skip(&UNTIL_COMMA);
+        assert_eq!(ctx.rest_str(), "f");
+        assert_eq!(
+            ctx.position(),
+            Position {
+                line: 1,
+                column: 2,
+                field: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_lines() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("a,1\r\nb,2\nc,3".to_string());
+        let lines: Vec<_> = ctx.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1].full_str(), "b,2");
+        let mut line = ctx.lines().nth(2).unwrap();
+        assert_eq!(line.take(&UNTIL_COMMA), Some("c"));
+        assert_eq!(
+            line.position(),
+            Position {
+                line: 2,
+                column: 2,
+                field: 1
+            }
+        );
+        // the parent cursor is untouched
+        assert_eq!(ctx.offset(), 0);
+    }
+
+    #[test]
+    fn test_take_strict_reports_line() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("a,1\nb".to_string());
+        let mut line = ctx.lines().nth(1).unwrap();
+        let report = line.take_strict(&UNTIL_COMMA).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.line(), 1);
+        assert_eq!(err.offset(), 0);
+    }
+
+    #[test]
+    fn test_take_field() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.init("a,,b*".to_string());
+        assert_eq!(ctx.take_field(&Char::<'a'>)?, Some('a'));
+        ctx.skip(&Char::<','>);
+        assert_eq!(ctx.take_field(&Char::<'a'>)?, None);
+        ctx.skip(&Char::<','>);
+        let cp = ctx.checkpoint();
+        assert_eq!(ctx.take_field(&Char::<'a'>)?, None);
+
+        ctx.rewind(cp)?;
+        ctx.field_mode(NumMode::Strict);
+        let report = ctx.take_field(&Char::<'a'>).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 3);
+        assert_eq!(err.rule(), Char::<'a'>.name());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_field() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.field_mode(NumMode::Strict);
+        ctx.init("12,,x1,".to_string());
+        assert_eq!(ctx.parse_field::<_, u8>(&UNTIL_COMMA)?, Some(12));
+        assert_eq!(ctx.parse_field::<_, u8>(&UNTIL_COMMA)?, None);
+        let report = ctx.parse_field::<_, u8>(&UNTIL_COMMA).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 4);
+        assert_eq!(err.rule(), "parse::<u8>");
+        // the cursor moved past the field
+        assert_eq!(ctx.parse_field::<_, u8>(&UNTIL_COMMA)?, None);
+
+        // lenient by default, and the mode is kept across `init`
+        let mut lenient = StrParserContext::new();
+        lenient.init("x1,".to_string());
+        assert_eq!(lenient.parse_field::<_, u8>(&UNTIL_COMMA)?, None);
+        ctx.init("x1,".to_string());
+        assert!(ctx.parse_field::<_, u8>(&UNTIL_COMMA).is_err());
+        Ok(())
+    }
+}
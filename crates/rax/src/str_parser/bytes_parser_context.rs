@@ -0,0 +1,196 @@
+use crate::str_parser::{BytesParserError, Checkpoint, IBytesFlowRule, IBytesGlobalRule};
+
+/// Byte counterpart of [`StrParserContext`] for binary protocols.
+///
+/// Owns the input buffer and a cursor; rules implementing
+/// [`IBytesFlowRule`] are applied at the cursor and advance it.
+///
+/// [`StrParserContext`]: crate::str_parser::StrParserContext
+pub struct BytesParserContext {
+    full: Vec<u8>,
+    pos: usize,
+    generation: usize,
+}
+
+impl Default for BytesParserContext {
+    fn default() -> Self { Self::new() }
+}
+
+impl BytesParserContext {
+    pub fn new() -> Self {
+        Self {
+            full: Vec::new(),
+            pos: 0,
+            generation: 0,
+        }
+    }
+    pub fn init(&mut self, input: Vec<u8>) -> &mut Self {
+        self.full = input;
+        self.pos = 0;
+        self.generation = self.generation.wrapping_add(1);
+        self
+    }
+
+    pub fn full_bytes(&self) -> &[u8] { &self.full }
+    pub fn rest_bytes(&self) -> &[u8] { &self.full[self.pos..] }
+    /// Byte offset of the cursor into `full_bytes()`.
+    pub fn offset(&self) -> usize { self.pos }
+    pub fn reset(&mut self) -> &mut Self {
+        self.pos = 0;
+        self
+    }
+
+    /// Save the current cursor position.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            pos: self.pos,
+            generation: self.generation,
+        }
+    }
+    /// Move the cursor back to a position saved by `checkpoint()`.
+    /// Fails if the context was re-initialized since.
+    pub fn rewind(&mut self, checkpoint: Checkpoint) -> miette::Result<&mut Self> {
+        if checkpoint.generation != self.generation {
+            miette::bail!("checkpoint was taken on a previous input");
+        }
+        self.pos = checkpoint.pos;
+        Ok(self)
+    }
+
+    fn apply_at<'a, R>(full: &'a [u8], pos: usize, rule: &R) -> (Option<R::Output>, usize)
+    where
+        R: IBytesFlowRule<'a>,
+    {
+        let (out, rest) = rule.apply(&full[pos..]);
+        (out, full.len() - rest.len())
+    }
+}
+
+impl BytesParserContext {
+    pub fn take<'a, R>(&'a mut self, rule: &R) -> Option<R::Output>
+    where
+        R: IBytesFlowRule<'a>,
+    {
+        let (out, pos) = Self::apply_at(&self.full, self.pos, rule);
+        self.pos = pos;
+        out
+    }
+    pub fn take_strict<'a, R>(&'a mut self, rule: &R) -> miette::Result<R::Output>
+    where
+        R: IBytesFlowRule<'a>,
+    {
+        let offset = self.pos;
+        let (out, pos) = Self::apply_at(&self.full, offset, rule);
+        self.pos = pos;
+        match out {
+            Some(s) => Ok(s),
+            None => {
+                clerk::debug!("take_strict: rule `{}` failed at {}", rule.name(), offset);
+                Err(BytesParserError::new(offset, rule.name(), self.full.len() - offset).into())
+            }
+        }
+    }
+    /// Like `take`, but leaves the cursor untouched if the rule fails.
+    pub fn try_take<'a, R>(&'a mut self, rule: &R) -> Option<R::Output>
+    where
+        R: IBytesFlowRule<'a>,
+    {
+        let (out, pos) = Self::apply_at(&self.full, self.pos, rule);
+        if out.is_some() {
+            self.pos = pos;
+        }
+        out
+    }
+    /// Apply `rule` at the cursor without consuming any input.
+    pub fn peek<'a, R>(&'a self, rule: &R) -> Option<R::Output>
+    where
+        R: IBytesFlowRule<'a>,
+    {
+        Self::apply_at(&self.full, self.pos, rule).0
+    }
+}
+
+impl BytesParserContext {
+    pub fn skip<R>(&mut self, rule: &R) -> &mut Self
+    where
+        R: for<'a> IBytesFlowRule<'a>,
+    {
+        self.pos = Self::apply_at(&self.full, self.pos, rule).1;
+        self
+    }
+    pub fn skip_strict<R>(&mut self, rule: &R) -> miette::Result<&mut Self>
+    where
+        R: for<'a> IBytesFlowRule<'a>,
+    {
+        self.take_strict(rule)?;
+        Ok(self)
+    }
+}
+impl<'a> BytesParserContext {
+    pub fn global<R>(&'a mut self, rule: &R) -> R::Output
+    where
+        R: IBytesGlobalRule<'a>,
+    {
+        rule.apply(&self.full)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::rules::{LengthPrefixed, LittleEndian, NBytes, UntilBytes, UntilMode};
+
+    const SYNC: UntilBytes = UntilBytes {
+        pattern: &[0xB5, 0x62],
+        mode: UntilMode::Discard,
+    };
+
+    #[test]
+    fn test_parse_frame() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = BytesParserContext::new();
+        // garbage, sync, class, id, length (LE u16), payload
+        ctx.init(vec![0x24, 0xB5, 0x62, 0x01, 0x07, 0x02, 0x00, 0xAA, 0xBB]);
+        let class = ctx
+            .skip_strict(&SYNC)?
+            .take_strict(&LittleEndian::<u8>::new())?;
+        let id = ctx.take_strict(&LittleEndian::<u8>::new())?;
+        assert_eq!((class, id), (0x01, 0x07));
+        let payload = ctx.take_strict(&LengthPrefixed {
+            len: LittleEndian::<u16>::new(),
+        })?;
+        assert_eq!(payload, &[0xAA, 0xBB]);
+        assert!(ctx.rest_bytes().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_take_strict_error() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = BytesParserContext::new();
+        ctx.init(vec![1, 2, 3]);
+        ctx.skip(&NBytes::<1>);
+        let report = ctx.take_strict(&NBytes::<4>).unwrap_err();
+        let err = report.downcast_ref::<BytesParserError>().unwrap();
+        assert_eq!(err.offset(), 1);
+        assert_eq!(err.rule(), "NBytes");
+        assert_eq!(err.remaining(), 2);
+    }
+
+    #[test]
+    fn test_peek_try_take_rewind() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = BytesParserContext::new();
+        ctx.init(vec![1, 2, 3]);
+        let cp = ctx.checkpoint();
+        assert_eq!(ctx.peek(&LittleEndian::<u16>::new()), Some(0x0201));
+        assert_eq!(ctx.try_take(&NBytes::<4>), None);
+        assert_eq!(ctx.offset(), 0);
+        assert_eq!(ctx.take(&NBytes::<2>), Some(&[1u8, 2][..]));
+        ctx.rewind(cp)?;
+        assert_eq!(ctx.rest_bytes(), &[1, 2, 3]);
+        Ok(())
+    }
+}
@@ -0,0 +1,12 @@
+mod many;
+pub use many::*;
+mod map;
+pub use map::*;
+mod opt;
+pub use opt::*;
+mod or;
+pub use or::*;
+mod sep_by;
+pub use sep_by::*;
+mod seq;
+pub use seq::*;
@@ -0,0 +1,124 @@
+use crate::str_parser::rules::{IRule, IStrFlowRule};
+
+/// Combinator that applies a rule repeatedly.
+/// Collects outputs until the inner rule fails, stops consuming input, or
+/// `max` repetitions are reached. Returns None and the original input if
+/// fewer than `min` repetitions matched.
+pub struct Many<R> {
+    pub rule: R,
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl<R> Many<R> {
+    /// Zero or more repetitions of `rule`.
+    pub const fn new(rule: R) -> Self {
+        Self {
+            rule,
+            min: 0,
+            max: None,
+        }
+    }
+}
+
+impl<R: IRule> IRule for Many<R> {
+    fn name(&self) -> &str { "Many" }
+}
+
+impl<'a, R: IStrFlowRule<'a>> IStrFlowRule<'a> for Many<R> {
+    type Output = Vec<R::Output>;
+    /// Applies the inner rule as long as it matches and returns all outputs.
+    fn apply(&self, input: &'a str) -> (Option<Self::Output>, &'a str) {
+        clerk::trace!(
+            "Many rule: input='{}', min={}, max={:?}",
+            input,
+            self.min,
+            self.max
+        );
+        let mut outputs = Vec::new();
+        let mut rest = input;
+        while self.max.is_none_or(|max| outputs.len() < max) {
+            match self.rule.apply(rest) {
+                (Some(out), new_rest) => {
+                    let progressed = new_rest.len() < rest.len();
+                    outputs.push(out);
+                    rest = new_rest;
+                    // A rule that matches without consuming would loop forever.
+                    if !progressed {
+                        break;
+                    }
+                }
+                (None, _) => break,
+            }
+        }
+        if outputs.len() < self.min {
+            clerk::debug!(
+                "Many: only {} repetitions of `{}`, expected at least {}",
+                outputs.len(),
+                self.rule.name(),
+                self.min
+            );
+            return (None, input);
+        }
+        clerk::debug!("Many: matched {} times, rest='{}'", outputs.len(), rest);
+        (Some(outputs), rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::combinators::Opt;
+    use crate::str_parser::filters::DIGITS;
+    use crate::str_parser::rules::{Char, OneOfCharSet};
+
+    #[test]
+    fn test_many_zero_or_more() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Many::new(OneOfCharSet(&DIGITS));
+        let (out, rest) = rule.apply("123abc");
+        assert_eq!(out, Some(vec!['1', '2', '3']));
+        assert_eq!(rest, "abc");
+
+        let (out, rest) = rule.apply("abc");
+        assert_eq!(out, Some(vec![]));
+        assert_eq!(rest, "abc");
+    }
+
+    #[test]
+    fn test_many_min() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Many {
+            rule: OneOfCharSet(&DIGITS),
+            min: 2,
+            max: None,
+        };
+        let (out, rest) = rule.apply("1abc");
+        assert_eq!(out, None);
+        assert_eq!(rest, "1abc");
+    }
+
+    #[test]
+    fn test_many_max() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Many {
+            rule: Char::<','>,
+            min: 0,
+            max: Some(2),
+        };
+        let (out, rest) = rule.apply(",,,,");
+        assert_eq!(out, Some(vec![',', ',']));
+        assert_eq!(rest, ",,");
+    }
+
+    #[test]
+    fn test_many_no_progress() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Many::new(Opt(Char::<'x'>));
+        let (out, rest) = rule.apply("abc");
+        assert_eq!(out, Some(vec![None]));
+        assert_eq!(rest, "abc");
+    }
+}
@@ -0,0 +1,61 @@
+use crate::str_parser::rules::{IRule, IStrFlowRule};
+
+/// Combinator that transforms the output of a rule with a function.
+/// The rest of the input is left untouched; if the inner rule fails, returns
+/// None and its rest.
+pub struct Map<R, F> {
+    pub rule: R,
+    pub f: F,
+}
+
+impl<R: IRule, F> IRule for Map<R, F> {
+    fn name(&self) -> &str { "Map" }
+}
+
+impl<'a, R, F, U> IStrFlowRule<'a> for Map<R, F>
+where
+    R: IStrFlowRule<'a>,
+    F: Fn(R::Output) -> U,
+{
+    type Output = U;
+    /// Applies the inner rule and maps its output with `f`.
+    fn apply(&self, input: &'a str) -> (Option<U>, &'a str) {
+        clerk::trace!("Map rule: input='{}', inner={}", input, self.rule.name());
+        let (out, rest) = self.rule.apply(input);
+        (out.map(&self.f), rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::rules::{CharCount, UntilChar, UntilMode};
+
+    #[test]
+    fn test_map() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Map {
+            rule: UntilChar::<','> {
+                mode: UntilMode::Discard,
+            },
+            f: |s: &str| s.parse::<u8>().ok(),
+        };
+        let (out, rest) = rule.apply("08,0.9");
+        assert_eq!(out, Some(Some(8)));
+        assert_eq!(rest, "0.9");
+    }
+
+    #[test]
+    fn test_map_fail() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Map {
+            rule: CharCount::<4>,
+            f: str::len,
+        };
+        let (out, rest) = rule.apply("abc");
+        assert_eq!(out, None);
+        assert_eq!(rest, "abc");
+    }
+}
@@ -0,0 +1,51 @@
+use crate::str_parser::rules::{IRule, IStrFlowRule};
+
+/// Combinator that makes a rule optional.
+/// Always matches: returns `Some(Some(output))` and the rest if the inner rule
+/// matches, otherwise `Some(None)` and the original input.
+pub struct Opt<R>(pub R);
+
+impl<R: IRule> IRule for Opt<R> {
+    fn name(&self) -> &str { "Opt" }
+}
+
+impl<'a, R: IStrFlowRule<'a>> IStrFlowRule<'a> for Opt<R> {
+    type Output = Option<R::Output>;
+    /// Applies the inner rule and never fails.
+    fn apply(&self, input: &'a str) -> (Option<Self::Output>, &'a str) {
+        clerk::trace!("Opt rule: input='{}', inner={}", input, self.0.name());
+        match self.0.apply(input) {
+            (Some(out), rest) => (Some(Some(out)), rest),
+            (None, _) => {
+                clerk::debug!("Opt: `{}` did not match, keep input", self.0.name());
+                (Some(None), input)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::rules::Char;
+
+    #[test]
+    fn test_opt_some() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Opt(Char::<'-'>);
+        let (out, rest) = rule.apply("-12");
+        assert_eq!(out, Some(Some('-')));
+        assert_eq!(rest, "12");
+    }
+
+    #[test]
+    fn test_opt_none() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Opt(Char::<'-'>);
+        let (out, rest) = rule.apply("12");
+        assert_eq!(out, Some(None));
+        assert_eq!(rest, "12");
+    }
+}
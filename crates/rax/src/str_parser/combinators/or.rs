@@ -0,0 +1,99 @@
+use crate::str_parser::rules::{IRule, IStrFlowRule};
+
+/// Combinator that tries a tuple of alternative rules in order.
+/// Returns the output of the first rule that matches together with its rest.
+/// All alternatives must produce the same output type. If none of them
+/// matches, returns None and the original input.
+pub struct Or<T>(pub T);
+
+macro_rules! impl_or {
+    ($first:ident $(, $rule:ident)*) => {
+        impl<$first: IRule $(, $rule: IRule)*> IRule for Or<($first, $($rule,)*)> {
+            fn name(&self) -> &str { "Or" }
+        }
+
+        impl<'a, $first: IStrFlowRule<'a> $(, $rule: IStrFlowRule<'a, Output = $first::Output>)*>
+            IStrFlowRule<'a> for Or<($first, $($rule,)*)>
+        {
+            type Output = $first::Output;
+            /// Applies each alternative to the same input and stops at the
+            /// first one that matches.
+            #[allow(non_snake_case)]
+            fn apply(&self, input: &'a str) -> (Option<Self::Output>, &'a str) {
+                clerk::trace!("Or rule: input='{}'", input);
+                let ($first, $($rule,)*) = &self.0;
+                if let (Some(out), rest) = $first.apply(input) {
+                    clerk::debug!("Or: rule `{}` matched, rest='{}'", $first.name(), rest);
+                    return (Some(out), rest);
+                }
+                $(
+                    if let (Some(out), rest) = $rule.apply(input) {
+                        clerk::debug!("Or: rule `{}` matched, rest='{}'", $rule.name(), rest);
+                        return (Some(out), rest);
+                    }
+                )*
+                clerk::debug!("Or: no alternative matched '{}'", input);
+                (None, input)
+            }
+        }
+    };
+}
+
+impl_or!(A, B);
+impl_or!(A, B, C);
+impl_or!(A, B, C, D);
+impl_or!(A, B, C, D, E);
+impl_or!(A, B, C, D, E, F);
+impl_or!(A, B, C, D, E, F, G);
+impl_or!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::rules::{Char, UntilChar, UntilMode};
+
+    #[test]
+    fn test_or_first() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Or((Char::<'N'>, Char::<'S'>));
+        let (out, rest) = rule.apply("N,");
+        assert_eq!(out, Some('N'));
+        assert_eq!(rest, ",");
+    }
+
+    #[test]
+    fn test_or_second() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Or((Char::<'N'>, Char::<'S'>));
+        let (out, rest) = rule.apply("S,");
+        assert_eq!(out, Some('S'));
+        assert_eq!(rest, ",");
+    }
+
+    #[test]
+    fn test_or_none() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Or((Char::<'N'>, Char::<'S'>, Char::<'E'>));
+        let (out, rest) = rule.apply("W,");
+        assert_eq!(out, None);
+        assert_eq!(rest, "W,");
+    }
+
+    #[test]
+    fn test_or_does_not_leak_failed_rest() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Or((
+            UntilChar::<'*'> {
+                mode: UntilMode::Discard,
+            },
+            UntilChar::<','> {
+                mode: UntilMode::Discard,
+            },
+        ));
+        let (out, rest) = rule.apply("a,b");
+        assert_eq!(out, Some("a"));
+        assert_eq!(rest, "b");
+    }
+}
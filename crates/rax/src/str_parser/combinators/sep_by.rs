@@ -0,0 +1,96 @@
+use crate::str_parser::rules::{IRule, IStrFlowRule};
+
+/// Combinator that matches a list of items separated by a separator rule,
+/// like `item (sep item)*`. Returns the item outputs and the rest of the
+/// input. A separator that is not followed by an item is left unconsumed.
+/// Matches an empty list if the first item does not match.
+pub struct SepBy<R, S> {
+    pub item: R,
+    pub sep: S,
+}
+
+impl<R: IRule, S: IRule> IRule for SepBy<R, S> {
+    fn name(&self) -> &str { "SepBy" }
+}
+
+impl<'a, R, S> IStrFlowRule<'a> for SepBy<R, S>
+where
+    R: IStrFlowRule<'a>,
+    S: IStrFlowRule<'a>,
+{
+    type Output = Vec<R::Output>;
+    /// Applies `item`, then alternates `sep` and `item` until either fails.
+    fn apply(&self, input: &'a str) -> (Option<Self::Output>, &'a str) {
+        clerk::trace!("SepBy rule: input='{}'", input);
+        let mut outputs = Vec::new();
+        let mut rest = match self.item.apply(input) {
+            (Some(out), rest) => {
+                outputs.push(out);
+                rest
+            }
+            (None, _) => return (Some(outputs), input),
+        };
+        loop {
+            let after_sep = match self.sep.apply(rest) {
+                (Some(_), after_sep) if after_sep.len() < rest.len() => after_sep,
+                _ => break,
+            };
+            match self.item.apply(after_sep) {
+                (Some(out), new_rest) => {
+                    outputs.push(out);
+                    rest = new_rest;
+                }
+                (None, _) => break,
+            }
+        }
+        clerk::debug!("SepBy: matched {} items, rest='{}'", outputs.len(), rest);
+        (Some(outputs), rest)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::filters::DIGITS;
+    use crate::str_parser::rules::{Char, NInCharSet};
+
+    const NUMBER: NInCharSet<'static, 2, 10> = NInCharSet(&DIGITS);
+
+    #[test]
+    fn test_sep_by() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = SepBy {
+            item: NUMBER,
+            sep: Char::<','>,
+        };
+        let (out, rest) = rule.apply("05,07,08*3B");
+        assert_eq!(out, Some(vec!["05", "07", "08"]));
+        assert_eq!(rest, "*3B");
+    }
+
+    #[test]
+    fn test_sep_by_trailing_separator() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = SepBy {
+            item: NUMBER,
+            sep: Char::<','>,
+        };
+        let (out, rest) = rule.apply("05,07,abc");
+        assert_eq!(out, Some(vec!["05", "07"]));
+        assert_eq!(rest, ",abc");
+    }
+
+    #[test]
+    fn test_sep_by_empty() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = SepBy {
+            item: NUMBER,
+            sep: Char::<','>,
+        };
+        let (out, rest) = rule.apply("abc");
+        assert_eq!(out, Some(vec![]));
+        assert_eq!(rest, "abc");
+    }
+}
@@ -0,0 +1,113 @@
+use crate::str_parser::rules::{IRule, IStrFlowRule};
+
+/// Combinator that applies a tuple of rules one after another.
+/// Each rule starts where the previous one stopped. If every rule matches,
+/// returns a tuple of their outputs and the rest of the input. If any rule
+/// fails, returns None and the original input.
+pub struct Seq<T>(pub T);
+
+macro_rules! impl_seq {
+    ($($rule:ident),+) => {
+        impl<$($rule: IRule),+> IRule for Seq<($($rule,)+)> {
+            fn name(&self) -> &str { "Seq" }
+        }
+
+        impl<'a, $($rule: IStrFlowRule<'a>),+> IStrFlowRule<'a> for Seq<($($rule,)+)> {
+            type Output = ($($rule::Output,)+);
+            /// Applies every rule in order, threading the rest of the input
+            /// from one rule to the next.
+            #[allow(non_snake_case)]
+            fn apply(&self, input: &'a str) -> (Option<Self::Output>, &'a str) {
+                clerk::trace!("Seq rule: input='{}'", input);
+                let ($($rule,)+) = &self.0;
+                let rest = input;
+                $(
+                    let ($rule, rest) = match $rule.apply(rest) {
+                        (Some(out), rest) => (out, rest),
+                        (None, _) => {
+                            clerk::debug!(
+                                "Seq: rule `{}` failed at '{}'",
+                                $rule.name(),
+                                rest
+                            );
+                            return (None, input);
+                        }
+                    };
+                )+
+                clerk::debug!("Seq matched, rest='{}'", rest);
+                (Some(($($rule,)+)), rest)
+            }
+        }
+    };
+}
+
+impl_seq!(A);
+impl_seq!(A, B);
+impl_seq!(A, B, C);
+impl_seq!(A, B, C, D);
+impl_seq!(A, B, C, D, E);
+impl_seq!(A, B, C, D, E, F);
+impl_seq!(A, B, C, D, E, F, G);
+impl_seq!(A, B, C, D, E, F, G, H);
+impl_seq!(A, B, C, D, E, F, G, H, I);
+impl_seq!(A, B, C, D, E, F, G, H, I, J);
+impl_seq!(A, B, C, D, E, F, G, H, I, J, K);
+impl_seq!(A, B, C, D, E, F, G, H, I, J, K, L);
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::rules::{Char, CharCount, UntilChar, UntilMode};
+
+    #[test]
+    fn test_seq_match() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Seq((
+            UntilChar::<','> {
+                mode: UntilMode::Discard,
+            },
+            Char::<'N'>,
+            Char::<','>,
+        ));
+        let (out, rest) = rule.apply("4807.038,N,01131.000");
+        assert_eq!(out, Some(("4807.038", 'N', ',')));
+        assert_eq!(rest, "01131.000");
+    }
+
+    #[test]
+    fn test_seq_single() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Seq((CharCount::<2>,));
+        let (out, rest) = rule.apply("abc");
+        assert_eq!(out, Some(("ab",)));
+        assert_eq!(rest, "c");
+    }
+
+    #[test]
+    fn test_seq_fail_restores_input() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Seq((
+            UntilChar::<','> {
+                mode: UntilMode::Discard,
+            },
+            Char::<'S'>,
+        ));
+        let (out, rest) = rule.apply("4807.038,N,01131.000");
+        assert_eq!(out, None);
+        assert_eq!(rest, "4807.038,N,01131.000");
+    }
+
+    #[test]
+    fn test_seq_by_reference() {
+        init_log_with_level(LogLevel::TRACE);
+        let comma = UntilChar::<','> {
+            mode: UntilMode::Discard,
+        };
+        let rule = Seq((&comma, &comma));
+        let (out, rest) = rule.apply("a,b,c");
+        assert_eq!(out, Some(("a", "b")));
+        assert_eq!(rest, "c");
+    }
+}
@@ -0,0 +1,159 @@
+use std::fmt;
+
+use miette::{Diagnostic, SourceSpan};
+
+/// Error returned when a rule fails to match inside a [`StrParserContext`].
+///
+/// Carries the byte offset into the full string, the name of the failing
+/// rule and the remaining input, so that reports rendered by miette underline
+/// the offending field.
+///
+/// [`StrParserContext`]: crate::str_parser::StrParserContext
+#[derive(Debug, Clone, Diagnostic)]
+#[diagnostic(code(rax::str_parser::rule_failed))]
+pub struct StrParserError {
+    #[source_code]
+    src: String,
+    #[label("rule `{rule}` failed here")]
+    span: SourceSpan,
+    offset: usize,
+    line: usize,
+    rule: String,
+}
+
+impl StrParserError {
+    /// Create an error for `rule` failing at byte `offset` of `full`.
+    /// The span covers the field starting at `offset`, up to the next `,`,
+    /// `*` or line break.
+    pub fn new(full: &str, offset: usize, rule: &str) -> Self {
+        let field_len = full[offset..]
+            .find([',', '*', '\r', '\n'])
+            .unwrap_or(full.len() - offset);
+        Self {
+            src: full.to_string(),
+            span: (offset, field_len).into(),
+            offset,
+            line: 0,
+            rule: rule.to_string(),
+        }
+    }
+    /// Set the zero-based line the failure happened on.
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = line;
+        self
+    }
+    /// Byte offset into the full string where the rule was applied.
+    pub fn offset(&self) -> usize { self.offset }
+    /// Zero-based line (fragment) the rule failed on.
+    pub fn line(&self) -> usize { self.line }
+    /// Name of the rule that failed.
+    pub fn rule(&self) -> &str { &self.rule }
+    /// The input that was left when the rule failed.
+    pub fn rest(&self) -> &str { &self.src[self.offset..] }
+    /// The full string being parsed.
+    pub fn full_str(&self) -> &str { &self.src }
+    /// Span of the offending field in the full string.
+    pub fn span(&self) -> SourceSpan { self.span }
+}
+
+impl fmt::Display for StrParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rule `{}` failed at line {}, byte {}: {:?}",
+            self.rule,
+            self.line,
+            self.offset,
+            self.rest()
+        )
+    }
+}
+
+impl std::error::Error for StrParserError {}
+
+/// Error returned when a rule fails to match inside a
+/// [`BytesParserContext`].
+///
+/// [`BytesParserContext`]: crate::str_parser::BytesParserContext
+#[derive(Debug, Clone, Diagnostic)]
+#[diagnostic(code(rax::str_parser::bytes_rule_failed))]
+pub struct BytesParserError {
+    offset: usize,
+    rule: String,
+    remaining: usize,
+}
+
+impl BytesParserError {
+    /// Create an error for `rule` failing at byte `offset` with `remaining`
+    /// bytes left.
+    pub fn new(offset: usize, rule: &str, remaining: usize) -> Self {
+        Self {
+            offset,
+            rule: rule.to_string(),
+            remaining,
+        }
+    }
+    /// Byte offset into the full input where the rule was applied.
+    pub fn offset(&self) -> usize { self.offset }
+    /// Name of the rule that failed.
+    pub fn rule(&self) -> &str { &self.rule }
+    /// Number of bytes that were left when the rule failed.
+    pub fn remaining(&self) -> usize { self.remaining }
+}
+
+impl fmt::Display for BytesParserError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rule `{}` failed at byte {} with {} bytes left",
+            self.rule, self.offset, self.remaining
+        )
+    }
+}
+
+impl std::error::Error for BytesParserError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::str_parser::StrParserContext;
+    use crate::str_parser::rules::{Char, UntilChar, UntilMode};
+
+    #[test]
+    fn test_error_fields() {
+        let err = StrParserError::new("$GPGGA,123519,x,N*47", 14, "NmeaCoord");
+        assert_eq!(err.offset(), 14);
+        assert_eq!(err.rule(), "NmeaCoord");
+        assert_eq!(err.rest(), "x,N*47");
+        assert_eq!(err.span(), SourceSpan::from((14, 1)));
+        assert_eq!(
+            err.to_string(),
+            "rule `NmeaCoord` failed at line 0, byte 14: \"x,N*47\""
+        );
+    }
+
+    #[test]
+    fn test_error_span_until_end() {
+        let err = StrParserError::new("abc", 1, "Until");
+        assert_eq!(err.span(), SourceSpan::from((1, 2)));
+        let err = StrParserError::new("abc", 3, "Until");
+        assert_eq!(err.span(), SourceSpan::from((3, 0)));
+        assert_eq!(err.rest(), "");
+    }
+
+    #[test]
+    fn test_take_strict_error() {
+        let mut ctx = StrParserContext::new();
+        ctx.init("$GPGGA,123519,x,N*47".to_string());
+        ctx.skip_strict(&UntilChar::<','> {
+            mode: UntilMode::Discard,
+        })
+        .unwrap();
+        let report = ctx.take_strict(&Char::<'x'>).unwrap_err();
+        let err = report.downcast_ref::<StrParserError>().unwrap();
+        assert_eq!(err.offset(), 7);
+        assert_eq!(err.rule(), "char");
+        assert_eq!(err.rest(), "123519,x,N*47");
+        assert_eq!(err.span(), SourceSpan::from((7, 6)));
+    }
+}
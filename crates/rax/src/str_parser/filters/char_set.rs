@@ -1,27 +1,129 @@
+use std::ops::RangeInclusive;
 use std::str::FromStr;
 
 use crate::str_parser::filters::IFilter;
 
-/// A fixed, sorted list of characters.
-/// `contains()` uses a const‑friendly binary search.
-/// No nightly features required.
+/// A set of characters.
+///
+/// ASCII members live in a 128-bit bitmap, so lookups are a single bit test.
+/// Other characters fall back to a linear scan of `table`. Sets with at most
+/// three members, all ASCII, are scanned with `memchr` by
+/// [`CharSetFilter::find_in`].
 pub struct CharSetFilter<const N: usize> {
+    ascii: u128,
+    has_non_ascii: bool,
     table: [char; N],
 }
 
 impl<const N: usize> CharSetFilter<N> {
-    /// The caller promises `table` is sorted and unique.
-    pub const fn new(table: [char; N]) -> Self { Self { table } }
+    /// Build a set from a list of characters, in any order.
+    pub const fn new(table: [char; N]) -> Self {
+        let mut ascii = 0u128;
+        let mut has_non_ascii = false;
+        let mut i = 0;
+        while i < N {
+            let c = table[i] as u32;
+            if c < 128 {
+                ascii |= 1 << c;
+            } else {
+                has_non_ascii = true;
+            }
+            i += 1;
+        }
+        Self {
+            ascii,
+            has_non_ascii,
+            table,
+        }
+    }
+    /// Add every character of the given ASCII ranges to the set.
+    ///
+    /// # Panics
+    /// If a range reaches outside ASCII; list such characters in `new`
+    /// instead.
+    pub const fn with_ranges(mut self, ranges: &[RangeInclusive<char>]) -> Self {
+        let mut i = 0;
+        while i < ranges.len() {
+            let (start, end) = (*ranges[i].start() as u32, *ranges[i].end() as u32);
+            assert!(end < 128, "CharSetFilter ranges must be ASCII");
+            let mut c = start;
+            while c <= end {
+                self.ascii |= 1 << c;
+                c += 1;
+            }
+            i += 1;
+        }
+        self
+    }
+    /// Whether `c` is in the set.
+    #[inline]
+    pub fn contains(&self, c: char) -> bool {
+        if c.is_ascii() {
+            self.ascii & (1 << c as u32) != 0
+        } else {
+            self.has_non_ascii && self.table.contains(&c)
+        }
+    }
+    /// Byte index of the first character of `input` in the set.
+    pub fn find_in(&self, input: &str) -> Option<usize> {
+        if self.has_non_ascii {
+            return input.find(|c| self.contains(c));
+        }
+        // The set is ASCII only, so a byte scan may only stop at bytes < 0x80;
+        // those are always char boundaries. Bytes >= 0x80 are outside the
+        // 128-bit bitmap and must be skipped before the bit test.
+        let bytes = input.as_bytes();
+        match self.ascii.count_ones() {
+            0 => None,
+            1 => memchr::memchr(self.nth_ascii(0), bytes),
+            2 => memchr::memchr2(self.nth_ascii(0), self.nth_ascii(1), bytes),
+            3 => memchr::memchr3(
+                self.nth_ascii(0),
+                self.nth_ascii(1),
+                self.nth_ascii(2),
+                bytes,
+            ),
+            _ => bytes
+                .iter()
+                .position(|&b| b < 128 && self.ascii & (1 << b) != 0),
+        }
+    }
+    /// Byte index of the first character of `input` not in the set.
+    pub fn find_not_in(&self, input: &str) -> Option<usize> {
+        if self.has_non_ascii {
+            return input.find(|c| !self.contains(c));
+        }
+        // A set without non-ASCII members stops at the first byte >= 0x80,
+        // which is always a char boundary here.
+        input
+            .as_bytes()
+            .iter()
+            .position(|&b| b >= 128 || self.ascii & (1 << b) == 0)
+    }
+    /// The `n`th ASCII member, in byte order.
+    fn nth_ascii(&self, n: u32) -> u8 {
+        let mut bits = self.ascii;
+        for _ in 0..n {
+            bits &= bits - 1;
+        }
+        bits.trailing_zeros() as u8
+    }
+}
+impl CharSetFilter<0> {
+    /// Build an ASCII set from ranges, e.g.
+    /// `CharSetFilter::from_ranges(&['0'..='9', 'A'..='F'])`.
+    ///
+    /// # Panics
+    /// If a range reaches outside ASCII.
+    pub const fn from_ranges(ranges: &[RangeInclusive<char>]) -> Self {
+        Self::new([]).with_ranges(ranges)
+    }
 }
 impl<const N: usize> IFilter<&char> for CharSetFilter<N> {
-    fn name(&self) -> &str { "Char Set (array)" }
+    fn name(&self) -> &str { "Char Set (bitmap)" }
     fn filter(&self, input: &char) -> bool {
-        clerk::trace!(
-            "CharSetFilter: checking if '{}' is in the set {:?}",
-            input,
-            self.table
-        );
-        self.table.contains(input)
+        clerk::trace!("CharSetFilter: checking if '{}' is in the set", input);
+        self.contains(*input)
     }
 }
 impl<const N: usize> FromStr for CharSetFilter<N> {
@@ -53,11 +155,11 @@ impl<const N: usize> FromStr for CharSetFilter<N> {
     }
 }
 
-/// Digits (10 items) – lookup is ~3 comparisons.
+/// Digits (10 items).
 pub const DIGITS: CharSetFilter<10> =
     CharSetFilter::new(['0', '1', '2', '3', '4', '5', '6', '7', '8', '9']);
 
-/// ASCII letters (52 items) – lookup is ~6 comparisons.
+/// ASCII letters (52 items).
 /// This includes both uppercase and lowercase letters.
 pub const ASCII_LETTERS: CharSetFilter<52> = CharSetFilter::new([
     'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O', 'P', 'Q', 'R', 'S',
@@ -206,4 +308,36 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_char_set_filter_ranges() {
+        init_log_with_level(LogLevel::TRACE);
+        const HEX: CharSetFilter<0> = CharSetFilter::from_ranges(&['0'..='9', 'A'..='F']);
+        assert!(HEX.contains('0'));
+        assert!(HEX.contains('9'));
+        assert!(HEX.contains('C'));
+        assert!(!HEX.contains('G'));
+        assert!(!HEX.contains('a'));
+        assert!(!HEX.contains('é'));
+        let filter = CharSetFilter::new(['é']).with_ranges(&['a'..='c']);
+        assert!(filter.contains('é'));
+        assert!(filter.contains('b'));
+        assert!(!filter.contains('d'));
+    }
+    #[test]
+    fn test_char_set_filter_find() {
+        init_log_with_level(LogLevel::TRACE);
+        let few = CharSetFilter::new([',', '*']);
+        assert_eq!(few.find_in("ab,c*"), Some(2));
+        assert_eq!(few.find_in("ü*"), Some(2));
+        assert_eq!(few.find_in("abc"), None);
+        assert_eq!(DIGITS.find_in("ab7"), Some(2));
+        assert_eq!(DIGITS.find_in("ab°7"), Some(4));
+        assert_eq!(DIGITS.find_in("°ü"), None);
+        assert_eq!(DIGITS.find_not_in("12é3"), Some(2));
+        assert_eq!(DIGITS.find_not_in("123"), None);
+        let unicode = CharSetFilter::new(['い', 'a']);
+        assert_eq!(unicode.find_in("xyいa"), Some(2));
+        assert_eq!(unicode.find_not_in("aいb"), Some(4));
+    }
 }
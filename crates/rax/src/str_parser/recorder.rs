@@ -0,0 +1,280 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+/// One rule application captured by a [`Recorder`].
+///
+/// Scopes opened with [`StrParserContext::enter`] are recorded as well and
+/// hold the rules applied inside them as `children`.
+///
+/// [`StrParserContext::enter`]: crate::str_parser::StrParserContext::enter
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleRecord {
+    pub rule: String,
+    /// Byte offset the rule was applied at.
+    pub start: usize,
+    /// Byte offset of the cursor after the rule.
+    pub end: usize,
+    pub success: bool,
+    pub elapsed: Duration,
+    pub children: Vec<RuleRecord>,
+}
+
+/// Aggregated timings of one rule, see [`Recorder::profile`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleProfile {
+    pub rule: String,
+    pub calls: usize,
+    pub failures: usize,
+    pub total: Duration,
+}
+
+/// Opt-in recorder of the rules applied through a
+/// [`StrParserContext`](crate::str_parser::StrParserContext).
+///
+/// Records accumulate across inputs until the recorder is taken or cleared.
+#[derive(Debug, Clone, Default)]
+pub struct Recorder {
+    roots: Vec<RuleRecord>,
+    open: Vec<(RuleRecord, Instant)>,
+}
+
+impl Recorder {
+    pub fn new() -> Self { Self::default() }
+
+    /// Top level records, in application order.
+    pub fn records(&self) -> &[RuleRecord] { &self.roots }
+    pub fn clear(&mut self) {
+        self.roots.clear();
+        self.open.clear();
+    }
+
+    pub(crate) fn enter(&mut self, rule: &str, start: usize) {
+        let record = RuleRecord {
+            rule: rule.to_string(),
+            start,
+            end: start,
+            success: false,
+            elapsed: Duration::ZERO,
+            children: Vec::new(),
+        };
+        self.open.push((record, Instant::now()));
+    }
+    pub(crate) fn exit(&mut self, end: usize, success: bool) {
+        if let Some((mut record, started)) = self.open.pop() {
+            record.end = end;
+            record.success = success;
+            record.elapsed = started.elapsed();
+            self.push(record);
+        } else {
+            clerk::warn!("Recorder: exit without a matching enter");
+        }
+    }
+    /// Close scopes left open, e.g. by an early `?` return, as failed.
+    pub(crate) fn close_all(&mut self, end: usize) {
+        while !self.open.is_empty() {
+            self.exit(end, false);
+        }
+    }
+    pub(crate) fn leaf(
+        &mut self,
+        rule: &str,
+        start: usize,
+        end: usize,
+        success: bool,
+        elapsed: Duration,
+    ) {
+        self.push(RuleRecord {
+            rule: rule.to_string(),
+            start,
+            end,
+            success,
+            elapsed,
+            children: Vec::new(),
+        });
+    }
+    fn push(&mut self, record: RuleRecord) {
+        match self.open.last_mut() {
+            Some((parent, _)) => parent.children.push(record),
+            None => self.roots.push(record),
+        }
+    }
+
+    /// Per-rule call counts and total time, slowest rule first. Time spent in
+    /// a scope includes its children.
+    pub fn profile(&self) -> Vec<RuleProfile> {
+        fn walk(records: &[RuleRecord], out: &mut Vec<RuleProfile>) {
+            for r in records {
+                let idx = match out.iter().position(|p| p.rule == r.rule) {
+                    Some(idx) => idx,
+                    None => {
+                        out.push(RuleProfile {
+                            rule: r.rule.clone(),
+                            calls: 0,
+                            failures: 0,
+                            total: Duration::ZERO,
+                        });
+                        out.len() - 1
+                    }
+                };
+                out[idx].calls += 1;
+                out[idx].failures += usize::from(!r.success);
+                out[idx].total += r.elapsed;
+                walk(&r.children, out);
+            }
+        }
+        let mut out = Vec::new();
+        walk(&self.roots, &mut out);
+        out.sort_by_key(|p| std::cmp::Reverse(p.total));
+        out
+    }
+
+    /// Dump the records as a JSON array. Elapsed times are in nanoseconds.
+    pub fn to_json(&self) -> String {
+        fn write_records(out: &mut String, records: &[RuleRecord]) {
+            out.push('[');
+            for (i, r) in records.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str("{\"rule\":\"");
+                for c in r.rule.chars() {
+                    match c {
+                        '"' => out.push_str("\\\""),
+                        '\\' => out.push_str("\\\\"),
+                        c if c.is_control() => {
+                            let _ = write!(out, "\\u{:04x}", c as u32);
+                        }
+                        c => out.push(c),
+                    }
+                }
+                let _ = write!(
+                    out,
+                    "\",\"start\":{},\"end\":{},\"success\":{},\"elapsed_ns\":{},\"children\":",
+                    r.start,
+                    r.end,
+                    r.success,
+                    r.elapsed.as_nanos()
+                );
+                write_records(out, &r.children);
+                out.push('}');
+            }
+            out.push(']');
+        }
+        let mut out = String::new();
+        write_records(&mut out, &self.roots);
+        out
+    }
+
+    /// Render the records as an indented tree, one rule per line.
+    pub fn to_tree(&self) -> String {
+        fn write_records(out: &mut String, records: &[RuleRecord], depth: usize) {
+            for r in records {
+                let _ = writeln!(
+                    out,
+                    "{:indent$}{} [{}..{}] {} {:?}",
+                    "",
+                    r.rule,
+                    r.start,
+                    r.end,
+                    if r.success { "ok" } else { "failed" },
+                    r.elapsed,
+                    indent = depth * 2
+                );
+                write_records(out, &r.children, depth + 1);
+            }
+        }
+        let mut out = String::new();
+        write_records(&mut out, &self.roots, 0);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use crate::str_parser::StrParserContext;
+    use crate::str_parser::rules::{Char, UntilChar, UntilMode};
+
+    const UNTIL_COMMA: UntilChar<','> = UntilChar {
+        mode: UntilMode::Discard,
+    };
+
+    fn recorded() -> StrParserContext {
+        let mut ctx = StrParserContext::new();
+        ctx.enable_recorder().init("$GP,1,x".to_string());
+        ctx.enter("Sentence");
+        ctx.skip(&UNTIL_COMMA);
+        let _ = ctx.take(&UNTIL_COMMA);
+        let _ = ctx.try_take(&Char::<'y'>);
+        ctx.exit();
+        ctx
+    }
+
+    #[test]
+    fn test_record_tree() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = recorded();
+        let recorder = ctx.take_recorder().unwrap();
+        let records = recorder.records();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].rule, "Sentence");
+        assert_eq!((records[0].start, records[0].end), (0, 6));
+        assert!(records[0].success);
+        let children: Vec<_> = records[0]
+            .children
+            .iter()
+            .map(|r| (r.rule.as_str(), r.start, r.end, r.success))
+            .collect();
+        assert_eq!(
+            children,
+            [
+                ("Until", 0, 4, true),
+                ("Until", 4, 6, true),
+                ("char", 6, 6, false)
+            ]
+        );
+        assert!(ctx.recorder().is_none());
+    }
+
+    #[test]
+    fn test_unclosed_scope_is_failed() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrParserContext::new();
+        ctx.enable_recorder().init("a,b".to_string());
+        ctx.enter("Sentence").skip(&UNTIL_COMMA);
+        ctx.init("c".to_string());
+        let records = ctx.recorder().unwrap().records();
+        assert_eq!(records.len(), 1);
+        assert!(!records[0].success);
+        assert_eq!(records[0].children.len(), 1);
+    }
+
+    #[test]
+    fn test_json_and_tree() {
+        init_log_with_level(LogLevel::TRACE);
+        let ctx = recorded();
+        let recorder = ctx.recorder().unwrap();
+        let json = recorder.to_json();
+        assert!(json.starts_with(r#"[{"rule":"Sentence","start":0,"end":6,"success":true,"#));
+        assert!(json.contains(r#"{"rule":"char","start":6,"end":6,"success":false,"#));
+        let tree = recorder.to_tree();
+        let lines: Vec<_> = tree.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("Sentence [0..6] ok"));
+        assert!(lines[3].starts_with("  char [6..6] failed"));
+    }
+
+    #[test]
+    fn test_profile() {
+        init_log_with_level(LogLevel::TRACE);
+        let ctx = recorded();
+        let profile = ctx.recorder().unwrap().profile();
+        assert_eq!(profile.len(), 3);
+        assert_eq!(profile[0].rule, "Sentence");
+        let until = profile.iter().find(|p| p.rule == "Until").unwrap();
+        assert_eq!((until.calls, until.failures), (2, 0));
+        let char = profile.iter().find(|p| p.rule == "char").unwrap();
+        assert_eq!((char.calls, char.failures), (1, 1));
+    }
+}
@@ -20,6 +20,16 @@ mod n_in_charset;
 pub use n_in_charset::*;
 mod until_char;
 pub use until_char::*;
+mod endian;
+pub use endian::*;
+mod n_bytes;
+pub use n_bytes::*;
+mod length_prefixed;
+pub use length_prefixed::*;
+mod until_bytes;
+pub use until_bytes::*;
+mod number;
+pub use number::*;
 #[derive(Clone, Copy, Debug)]
 pub enum UntilMode {
     /// Drop the delimiter completely → ("a", "b")
@@ -49,3 +59,56 @@ pub trait IStrGlobalRule<'a>: IRule {
     type Output;
     fn apply(&self, input: &'a str) -> Self::Output;
 }
+/// Outcome of a rule applied to input that may still be growing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Partial<T> {
+    /// The rule matched.
+    Done(T),
+    /// The input ended before the rule could decide. `needed` is the minimum
+    /// number of extra bytes, if known.
+    Incomplete { needed: Option<usize> },
+    /// The rule cannot match, whatever input follows.
+    Fail,
+}
+impl<T> Partial<T> {
+    /// Converts into an `Option`, treating `Incomplete` as no match.
+    pub fn done(self) -> Option<T> {
+        match self {
+            Partial::Done(out) => Some(out),
+            _ => None,
+        }
+    }
+    pub fn is_incomplete(&self) -> bool { matches!(self, Partial::Incomplete { .. }) }
+}
+/// Rule that can be applied to a prefix of a stream, telling "no match" apart
+/// from "not enough input yet".
+pub trait IStrStreamRule<'a>: IRule {
+    type Output;
+    fn apply_partial(&self, input: &'a str) -> (Partial<Self::Output>, &'a str);
+}
+pub trait IBytesFlowRule<'a>: IRule {
+    type Output;
+    fn apply(&self, input: &'a [u8]) -> (Option<Self::Output>, &'a [u8]);
+}
+pub trait IBytesGlobalRule<'a>: IRule {
+    type Output;
+    fn apply(&self, input: &'a [u8]) -> Self::Output;
+}
+
+impl<R: IRule + ?Sized> IRule for &R {
+    fn name(&self) -> &str { (**self).name() }
+}
+impl<'a, R: IStrFlowRule<'a> + ?Sized> IStrFlowRule<'a> for &R {
+    type Output = R::Output;
+    fn apply(&self, input: &'a str) -> (Option<Self::Output>, &'a str) { (**self).apply(input) }
+}
+impl<'a, R: IBytesFlowRule<'a> + ?Sized> IBytesFlowRule<'a> for &R {
+    type Output = R::Output;
+    fn apply(&self, input: &'a [u8]) -> (Option<Self::Output>, &'a [u8]) { (**self).apply(input) }
+}
+impl<'a, R: IStrStreamRule<'a> + ?Sized> IStrStreamRule<'a> for &R {
+    type Output = R::Output;
+    fn apply_partial(&self, input: &'a str) -> (Partial<Self::Output>, &'a str) {
+        (**self).apply_partial(input)
+    }
+}
@@ -1,4 +1,4 @@
-use super::IStrFlowRule;
+use super::{IStrFlowRule, IStrStreamRule, Partial};
 use crate::str_parser::rules::IRule;
 
 /// Rule to extract a fixed number of bytes from the input string.
@@ -38,6 +38,23 @@ impl<'a, const N: usize> IStrFlowRule<'a> for ByteCount<N> {
     }
 }
 
+impl<'a, const N: usize> IStrStreamRule<'a> for ByteCount<N> {
+    type Output = &'a str;
+    /// Too few bytes is `Incomplete`; a split inside a character is `Fail`.
+    fn apply_partial(&self, input: &'a str) -> (Partial<&'a str>, &'a str) {
+        match self.apply(input) {
+            (Some(out), rest) => (Partial::Done(out), rest),
+            (None, rest) if input.len() < N => (
+                Partial::Incomplete {
+                    needed: Some(N - input.len()),
+                },
+                rest,
+            ),
+            (None, rest) => (Partial::Fail, rest),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -102,4 +119,16 @@ mod tests {
         let result = rule.apply(input);
         assert_eq!(result, (None, "你好世界"));
     }
+
+    #[test]
+    fn test_count_partial() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = ByteCount::<4>;
+        assert_eq!(
+            rule.apply_partial("ab"),
+            (Partial::Incomplete { needed: Some(2) }, "ab")
+        );
+        assert_eq!(rule.apply_partial("abcde"), (Partial::Done("abcd"), "e"));
+        assert_eq!(rule.apply_partial("abc你"), (Partial::Fail, "abc你"));
+    }
 }
@@ -1,4 +1,4 @@
-use super::IStrFlowRule;
+use super::{IStrFlowRule, IStrStreamRule, Partial};
 use crate::str_parser::rules::IRule;
 
 /// Rule to match a specific character at the start of the input string.
@@ -40,6 +40,18 @@ impl<'a, const C: char> IStrFlowRule<'a> for Char<C> {
     }
 }
 
+impl<'a, const C: char> IStrStreamRule<'a> for Char<C> {
+    type Output = char;
+    /// Empty input is `Incomplete`; any other mismatch is `Fail`.
+    fn apply_partial(&self, input: &'a str) -> (Partial<char>, &'a str) {
+        match self.apply(input) {
+            (Some(out), rest) => (Partial::Done(out), rest),
+            (None, rest) if input.is_empty() => (Partial::Incomplete { needed: Some(1) }, rest),
+            (None, rest) => (Partial::Fail, rest),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -86,4 +98,16 @@ mod tests {
         assert_eq!(matched, Some('你'));
         assert_eq!(rest, "好");
     }
+
+    #[test]
+    fn test_char_partial() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Char::<'$'>;
+        assert_eq!(
+            rule.apply_partial(""),
+            (Partial::Incomplete { needed: Some(1) }, "")
+        );
+        assert_eq!(rule.apply_partial("$G"), (Partial::Done('$'), "G"));
+        assert_eq!(rule.apply_partial("G"), (Partial::Fail, "G"));
+    }
 }
@@ -1,4 +1,4 @@
-use super::IStrFlowRule;
+use super::{IStrFlowRule, IStrStreamRule, Partial};
 use crate::str_parser::rules::IRule;
 
 /// Rule to extract a fixed number of characters from the input string.
@@ -60,6 +60,23 @@ impl<'a, const N: usize> IStrFlowRule<'a> for CharCount<N> {
     }
 }
 
+impl<'a, const N: usize> IStrStreamRule<'a> for CharCount<N> {
+    type Output = &'a str;
+    /// Too few characters is `Incomplete`; each missing char needs at least
+    /// one more byte.
+    fn apply_partial(&self, input: &'a str) -> (Partial<&'a str>, &'a str) {
+        match self.apply(input) {
+            (Some(out), rest) => (Partial::Done(out), rest),
+            (None, rest) => (
+                Partial::Incomplete {
+                    needed: Some(N - input.chars().count()),
+                },
+                rest,
+            ),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -127,4 +144,15 @@ mod tests {
         assert_eq!(prefix, Some("你好"));
         assert_eq!(rest, "世界");
     }
+
+    #[test]
+    fn test_count_partial() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = CharCount::<4>;
+        assert_eq!(
+            rule.apply_partial("ab"),
+            (Partial::Incomplete { needed: Some(2) }, "ab")
+        );
+        assert_eq!(rule.apply_partial("abcde"), (Partial::Done("abcd"), "e"));
+    }
 }
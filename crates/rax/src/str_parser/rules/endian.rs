@@ -0,0 +1,137 @@
+use std::marker::PhantomData;
+
+use super::IBytesFlowRule;
+use crate::str_parser::rules::IRule;
+
+/// Fixed-size numeric types that can be decoded from raw bytes.
+pub trait IEndianBytes: Sized {
+    /// Number of bytes occupied by the value.
+    const SIZE: usize;
+    /// Decode from exactly `SIZE` little-endian bytes.
+    fn from_le_slice(bytes: &[u8]) -> Self;
+    /// Decode from exactly `SIZE` big-endian bytes.
+    fn from_be_slice(bytes: &[u8]) -> Self;
+}
+
+macro_rules! impl_endian_bytes {
+    ($($t:ty),*) => {
+        $(
+            impl IEndianBytes for $t {
+                const SIZE: usize = size_of::<$t>();
+                fn from_le_slice(bytes: &[u8]) -> Self {
+                    <$t>::from_le_bytes(bytes.try_into().expect("slice length must equal SIZE"))
+                }
+                fn from_be_slice(bytes: &[u8]) -> Self {
+                    <$t>::from_be_bytes(bytes.try_into().expect("slice length must equal SIZE"))
+                }
+            }
+        )*
+    };
+}
+impl_endian_bytes!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+/// Rule to decode a little-endian integer or float from the start of the
+/// input. Returns the value and the rest, or None if the input is too short.
+pub struct LittleEndian<T>(PhantomData<T>);
+
+impl<T> LittleEndian<T> {
+    pub const fn new() -> Self { Self(PhantomData) }
+}
+impl<T> Default for LittleEndian<T> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T> IRule for LittleEndian<T> {
+    fn name(&self) -> &str { "LittleEndian" }
+}
+
+impl<'a, T: IEndianBytes> IBytesFlowRule<'a> for LittleEndian<T> {
+    type Output = T;
+    fn apply(&self, input: &'a [u8]) -> (Option<T>, &'a [u8]) {
+        clerk::trace!("LittleEndian rule: input={:02X?}, size={}", input, T::SIZE);
+        match input.split_at_checked(T::SIZE) {
+            Some((head, rest)) => (Some(T::from_le_slice(head)), rest),
+            None => {
+                clerk::debug!("LittleEndian: need {} bytes, got {}", T::SIZE, input.len());
+                (None, input)
+            }
+        }
+    }
+}
+
+/// Rule to decode a big-endian integer or float from the start of the input.
+/// Returns the value and the rest, or None if the input is too short.
+pub struct BigEndian<T>(PhantomData<T>);
+
+impl<T> BigEndian<T> {
+    pub const fn new() -> Self { Self(PhantomData) }
+}
+impl<T> Default for BigEndian<T> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<T> IRule for BigEndian<T> {
+    fn name(&self) -> &str { "BigEndian" }
+}
+
+impl<'a, T: IEndianBytes> IBytesFlowRule<'a> for BigEndian<T> {
+    type Output = T;
+    fn apply(&self, input: &'a [u8]) -> (Option<T>, &'a [u8]) {
+        clerk::trace!("BigEndian rule: input={:02X?}, size={}", input, T::SIZE);
+        match input.split_at_checked(T::SIZE) {
+            Some((head, rest)) => (Some(T::from_be_slice(head)), rest),
+            None => {
+                clerk::debug!("BigEndian: need {} bytes, got {}", T::SIZE, input.len());
+                (None, input)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_little_endian_int() {
+        init_log_with_level(LogLevel::TRACE);
+        let (out, rest) = LittleEndian::<u16>::new().apply(&[0x34, 0x12, 0xFF]);
+        assert_eq!(out, Some(0x1234));
+        assert_eq!(rest, &[0xFF]);
+
+        let (out, rest) = LittleEndian::<i32>::new().apply(&[0xFE, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(out, Some(-2));
+        assert!(rest.is_empty());
+    }
+
+    #[test]
+    fn test_big_endian_int() {
+        init_log_with_level(LogLevel::TRACE);
+        let (out, rest) = BigEndian::<u32>::new().apply(&[0x12, 0x34, 0x56, 0x78, 0x9A]);
+        assert_eq!(out, Some(0x12345678));
+        assert_eq!(rest, &[0x9A]);
+    }
+
+    #[test]
+    fn test_endian_float() {
+        init_log_with_level(LogLevel::TRACE);
+        let bytes = 1.5f32.to_le_bytes();
+        let (out, _) = LittleEndian::<f32>::new().apply(&bytes);
+        assert_approx_eq!(f32, out.unwrap(), 1.5);
+
+        let bytes = (-2.25f64).to_be_bytes();
+        let (out, _) = BigEndian::<f64>::new().apply(&bytes);
+        assert_approx_eq!(f64, out.unwrap(), -2.25);
+    }
+
+    #[test]
+    fn test_endian_too_short() {
+        init_log_with_level(LogLevel::TRACE);
+        let (out, rest) = LittleEndian::<u64>::new().apply(&[1, 2, 3]);
+        assert_eq!(out, None);
+        assert_eq!(rest, &[1, 2, 3]);
+    }
+}
@@ -0,0 +1,74 @@
+use super::IBytesFlowRule;
+use crate::str_parser::rules::IRule;
+
+/// Rule to extract a block whose length is given by a leading field.
+/// `len` decodes the length (e.g. `LittleEndian::<u16>`), then that many
+/// bytes are returned as the block. Returns None and the original input if
+/// the length cannot be decoded or the block is truncated.
+pub struct LengthPrefixed<L> {
+    pub len: L,
+}
+
+impl<L> IRule for LengthPrefixed<L> {
+    fn name(&self) -> &str { "LengthPrefixed" }
+}
+
+impl<'a, L> IBytesFlowRule<'a> for LengthPrefixed<L>
+where
+    L: IBytesFlowRule<'a>,
+    L::Output: TryInto<usize>,
+{
+    type Output = &'a [u8];
+    fn apply(&self, input: &'a [u8]) -> (Option<&'a [u8]>, &'a [u8]) {
+        clerk::trace!("LengthPrefixed rule: input={:02X?}", input);
+        let (Some(len), after_len) = self.len.apply(input) else {
+            clerk::debug!("LengthPrefixed: cannot decode length");
+            return (None, input);
+        };
+        let Ok(len) = len.try_into() else {
+            clerk::debug!("LengthPrefixed: length does not fit in usize");
+            return (None, input);
+        };
+        match after_len.split_at_checked(len) {
+            Some((block, rest)) => (Some(block), rest),
+            None => {
+                clerk::debug!(
+                    "LengthPrefixed: need {} bytes, got {}",
+                    len,
+                    after_len.len()
+                );
+                (None, input)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::rules::{BigEndian, LittleEndian};
+
+    #[test]
+    fn test_length_prefixed() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = LengthPrefixed {
+            len: LittleEndian::<u16>::new(),
+        };
+        let (out, rest) = rule.apply(&[0x02, 0x00, 0xAA, 0xBB, 0xCC]);
+        assert_eq!(out, Some(&[0xAAu8, 0xBB][..]));
+        assert_eq!(rest, &[0xCC]);
+    }
+
+    #[test]
+    fn test_length_prefixed_truncated() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = LengthPrefixed {
+            len: BigEndian::<u8>::new(),
+        };
+        let (out, rest) = rule.apply(&[0x03, 0xAA]);
+        assert_eq!(out, None);
+        assert_eq!(rest, &[0x03, 0xAA]);
+    }
+}
@@ -0,0 +1,48 @@
+use super::IBytesFlowRule;
+use crate::str_parser::rules::IRule;
+
+/// Rule to extract a fixed number of bytes from the input.
+/// Returns a tuple of (prefix, rest) if at least `N` bytes are present,
+/// otherwise returns None.
+pub struct NBytes<const N: usize>;
+
+impl<const N: usize> IRule for NBytes<N> {
+    fn name(&self) -> &str { "NBytes" }
+}
+
+impl<'a, const N: usize> IBytesFlowRule<'a> for NBytes<N> {
+    type Output = &'a [u8];
+    fn apply(&self, input: &'a [u8]) -> (Option<&'a [u8]>, &'a [u8]) {
+        clerk::trace!("NBytes rule: input={:02X?}, count={}", input, N);
+        match input.split_at_checked(N) {
+            Some((out, rest)) => (Some(out), rest),
+            None => {
+                clerk::debug!("NBytes: need {} bytes, got {}", N, input.len());
+                (None, input)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+
+    #[test]
+    fn test_n_bytes() {
+        init_log_with_level(LogLevel::TRACE);
+        let (out, rest) = NBytes::<2>.apply(&[1, 2, 3]);
+        assert_eq!(out, Some(&[1u8, 2][..]));
+        assert_eq!(rest, &[3]);
+    }
+
+    #[test]
+    fn test_n_bytes_too_short() {
+        init_log_with_level(LogLevel::TRACE);
+        let (out, rest) = NBytes::<4>.apply(&[1, 2, 3]);
+        assert_eq!(out, None);
+        assert_eq!(rest, &[1, 2, 3]);
+    }
+}
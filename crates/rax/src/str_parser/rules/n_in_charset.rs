@@ -11,7 +11,7 @@ impl<'a, const N: usize, const M: usize> IRule for NInCharSet<'a, N, M> {
     fn name(&self) -> &str { "NInCharSet" }
 }
 
-impl<'a, const N: usize, const M: usize> IStrFlowRule<'a> for NInCharSet<'a, N, M> {
+impl<'a, 'f, const N: usize, const M: usize> IStrFlowRule<'a> for NInCharSet<'f, N, M> {
     type Output = &'a str;
     /// Applies the NInCharSet rule to the input string.
     /// If the first N characters are all in the set, returns the matched
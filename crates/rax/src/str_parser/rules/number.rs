@@ -0,0 +1,380 @@
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use super::IStrFlowRule;
+use crate::str_parser::rules::IRule;
+
+/// How a numeric rule treats characters after the numeric prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumMode {
+    /// The number must fill the whole field: it has to be followed by `,`,
+    /// `*`, `\r`, `\n` or the end of input, otherwise it is malformed.
+    #[default]
+    Strict,
+    /// Only the numeric prefix is consumed; whatever follows is left in the
+    /// rest.
+    Lenient,
+}
+
+/// Outcome of a numeric rule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NumField<T> {
+    /// A number was parsed.
+    Value(T),
+    /// The field is empty ("no data"). Nothing is consumed.
+    Empty,
+    /// The field holds something that is not a valid number ("bad data").
+    /// Nothing is consumed.
+    Malformed,
+}
+impl<T> NumField<T> {
+    /// Converts into an `Option`, treating empty and malformed fields alike.
+    pub fn value(self) -> Option<T> {
+        match self {
+            NumField::Value(v) => Some(v),
+            _ => None,
+        }
+    }
+    pub fn is_empty(&self) -> bool { matches!(self, NumField::Empty) }
+    pub fn is_malformed(&self) -> bool { matches!(self, NumField::Malformed) }
+}
+
+fn is_field_end(b: u8) -> bool { matches!(b, b',' | b'*' | b'\r' | b'\n') }
+
+fn count_digits(bytes: &[u8]) -> usize { bytes.iter().take_while(|b| b.is_ascii_digit()).count() }
+
+fn count_sign(bytes: &[u8]) -> usize { usize::from(matches!(bytes.first(), Some(b'+' | b'-'))) }
+
+/// Shared driver for the numeric rules. `scan` returns the byte length of the
+/// numeric prefix (0 if there is none) and `parse` converts that prefix.
+fn apply_number<'a, T>(
+    name: &str,
+    mode: NumMode,
+    input: &'a str,
+    scan: impl FnOnce(&[u8]) -> usize,
+    parse: impl FnOnce(&str) -> Option<T>,
+) -> (Option<NumField<T>>, &'a str) {
+    clerk::trace!("{} rule: input='{}', mode={:?}", name, input, mode);
+    let bytes = input.as_bytes();
+    if bytes.first().is_none_or(|b| is_field_end(*b)) {
+        clerk::debug!("{}: empty field", name);
+        return (Some(NumField::Empty), input);
+    }
+    let len = scan(bytes);
+    if len == 0 {
+        clerk::debug!("{}: no numeric prefix in '{}'", name, input);
+        return (Some(NumField::Malformed), input);
+    }
+    if mode == NumMode::Strict && bytes.get(len).is_some_and(|b| !is_field_end(*b)) {
+        clerk::debug!("{}: trailing garbage after '{}'", name, &input[..len]);
+        return (Some(NumField::Malformed), input);
+    }
+    match parse(&input[..len]) {
+        Some(v) => (Some(NumField::Value(v)), &input[len..]),
+        None => {
+            clerk::debug!("{}: '{}' is out of range", name, &input[..len]);
+            (Some(NumField::Malformed), input)
+        }
+    }
+}
+
+/// Primitive integer types accepted by [`Int`].
+pub trait IIntNumber: FromStr {}
+macro_rules! impl_int_number {
+    ($($t:ty),*) => { $(impl IIntNumber for $t {})* };
+}
+impl_int_number!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize
+);
+
+/// Rule to parse a decimal integer with an optional sign. Values that do not
+/// fit `T` are malformed.
+pub struct Int<T> {
+    mode: NumMode,
+    _marker: PhantomData<T>,
+}
+impl<T> Int<T> {
+    pub const fn new(mode: NumMode) -> Self {
+        Self {
+            mode,
+            _marker: PhantomData,
+        }
+    }
+}
+impl<T> Default for Int<T> {
+    fn default() -> Self { Self::new(NumMode::Strict) }
+}
+impl<T> IRule for Int<T> {
+    fn name(&self) -> &str { "Int" }
+}
+impl<'a, T: IIntNumber> IStrFlowRule<'a> for Int<T> {
+    type Output = NumField<T>;
+    fn apply(&self, input: &'a str) -> (Option<NumField<T>>, &'a str) {
+        apply_number(
+            self.name(),
+            self.mode,
+            input,
+            |b| {
+                let sign = count_sign(b);
+                match count_digits(&b[sign..]) {
+                    0 => 0,
+                    n => sign + n,
+                }
+            },
+            |s| s.parse().ok(),
+        )
+    }
+}
+
+/// Rule to parse a decimal floating point number such as `-12.5`, `.5`,
+/// `3.` or `1.5e3`.
+#[derive(Default)]
+pub struct Float {
+    mode: NumMode,
+}
+impl Float {
+    pub const fn new(mode: NumMode) -> Self { Self { mode } }
+}
+impl IRule for Float {
+    fn name(&self) -> &str { "Float" }
+}
+impl<'a> IStrFlowRule<'a> for Float {
+    type Output = NumField<f64>;
+    fn apply(&self, input: &'a str) -> (Option<NumField<f64>>, &'a str) {
+        apply_number(
+            self.name(),
+            self.mode,
+            input,
+            |b| {
+                let sign = count_sign(b);
+                let int = count_digits(&b[sign..]);
+                let mut len = sign + int;
+                let mut frac = 0;
+                if b.get(len) == Some(&b'.') {
+                    frac = count_digits(&b[len + 1..]);
+                    len += 1 + frac;
+                }
+                if int + frac == 0 {
+                    return 0;
+                }
+                // Only take the exponent if it is complete.
+                if matches!(b.get(len), Some(b'e' | b'E')) {
+                    let exp_sign = count_sign(&b[len + 1..]);
+                    let exp = count_digits(&b[len + 1 + exp_sign..]);
+                    if exp > 0 {
+                        len += 1 + exp_sign + exp;
+                    }
+                }
+                len
+            },
+            |s| s.parse().ok(),
+        )
+    }
+}
+
+/// Rule to parse exactly two hexadecimal digits into a byte, as used by NMEA
+/// checksums.
+#[derive(Default)]
+pub struct HexByte {
+    mode: NumMode,
+}
+impl HexByte {
+    pub const fn new(mode: NumMode) -> Self { Self { mode } }
+}
+impl IRule for HexByte {
+    fn name(&self) -> &str { "HexByte" }
+}
+impl<'a> IStrFlowRule<'a> for HexByte {
+    type Output = NumField<u8>;
+    fn apply(&self, input: &'a str) -> (Option<NumField<u8>>, &'a str) {
+        apply_number(
+            self.name(),
+            self.mode,
+            input,
+            |b| {
+                if b.len() >= 2 && b[..2].iter().all(u8::is_ascii_hexdigit) {
+                    2
+                } else {
+                    0
+                }
+            },
+            |s| u8::from_str_radix(s, 16).ok(),
+        )
+    }
+}
+
+/// Rule to parse a decimal number into an `i64` scaled by `10^N`, so
+/// `4807.038` with `N = 3` becomes `4807038`.
+///
+/// Missing fraction digits are padded with zeros. More than `N` fraction
+/// digits are malformed in strict mode and truncated in lenient mode.
+#[derive(Default)]
+pub struct FixedPoint<const N: u32> {
+    mode: NumMode,
+}
+impl<const N: u32> FixedPoint<N> {
+    pub const fn new(mode: NumMode) -> Self { Self { mode } }
+}
+impl<const N: u32> IRule for FixedPoint<N> {
+    fn name(&self) -> &str { "FixedPoint" }
+}
+impl<'a, const N: u32> IStrFlowRule<'a> for FixedPoint<N> {
+    type Output = NumField<i64>;
+    fn apply(&self, input: &'a str) -> (Option<NumField<i64>>, &'a str) {
+        let mode = self.mode;
+        apply_number(
+            self.name(),
+            mode,
+            input,
+            |b| {
+                let sign = count_sign(b);
+                let int = count_digits(&b[sign..]);
+                let mut len = sign + int;
+                let mut frac = 0;
+                if b.get(len) == Some(&b'.') {
+                    frac = count_digits(&b[len + 1..]);
+                    len += 1 + frac;
+                }
+                if int + frac == 0 { 0 } else { len }
+            },
+            |s| {
+                let (negative, s) = match s.as_bytes()[0] {
+                    b'-' => (true, &s[1..]),
+                    b'+' => (false, &s[1..]),
+                    _ => (false, s),
+                };
+                let (int, frac) = s.split_once('.').unwrap_or((s, ""));
+                if mode == NumMode::Strict && frac.len() > N as usize {
+                    return None;
+                }
+                let mut value: i64 = 0;
+                let digits = int
+                    .bytes()
+                    .chain(frac.bytes().chain(std::iter::repeat(b'0')).take(N as usize));
+                for d in digits {
+                    value = value.checked_mul(10)?.checked_add(i64::from(d - b'0'))?;
+                }
+                Some(if negative { -value } else { value })
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+    use float_cmp::assert_approx_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_int() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Int::<u8>::new(NumMode::Strict);
+        assert_eq!(rule.apply("42,rest"), (Some(NumField::Value(42)), ",rest"));
+        assert_eq!(rule.apply("7"), (Some(NumField::Value(7)), ""));
+        assert_eq!(rule.apply("+7*"), (Some(NumField::Value(7)), "*"));
+        let rule = Int::<i32>::default();
+        assert_eq!(rule.apply("-15\r\n"), (Some(NumField::Value(-15)), "\r\n"));
+    }
+
+    #[test]
+    fn test_int_empty_vs_malformed() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Int::<u8>::new(NumMode::Strict);
+        assert_eq!(rule.apply(""), (Some(NumField::Empty), ""));
+        assert_eq!(rule.apply(",1"), (Some(NumField::Empty), ",1"));
+        assert_eq!(rule.apply("x1,"), (Some(NumField::Malformed), "x1,"));
+        assert_eq!(rule.apply("-,"), (Some(NumField::Malformed), "-,"));
+        assert_eq!(rule.apply("12a,"), (Some(NumField::Malformed), "12a,"));
+        assert_eq!(rule.apply("256,"), (Some(NumField::Malformed), "256,"));
+        assert_eq!(rule.apply("-1,"), (Some(NumField::Malformed), "-1,"));
+    }
+
+    #[test]
+    fn test_int_lenient() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Int::<u16>::new(NumMode::Lenient);
+        assert_eq!(rule.apply("12a,"), (Some(NumField::Value(12)), "a,"));
+        assert_eq!(rule.apply("a12"), (Some(NumField::Malformed), "a12"));
+    }
+
+    #[test]
+    fn test_float() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Float::new(NumMode::Strict);
+        for (input, expected, rest) in [
+            ("4807.038,N", 4807.038, ",N"),
+            ("-0.5", -0.5, ""),
+            (".5*", 0.5, "*"),
+            ("3.,", 3.0, ","),
+            ("1.5e3,", 1500.0, ","),
+        ] {
+            let (out, r) = rule.apply(input);
+            assert_approx_eq!(f64, out.unwrap().value().unwrap(), expected);
+            assert_eq!(r, rest);
+        }
+        assert_eq!(rule.apply(",1.0"), (Some(NumField::Empty), ",1.0"));
+        assert_eq!(rule.apply(".,"), (Some(NumField::Malformed), ".,"));
+        assert_eq!(rule.apply("1.2.3,"), (Some(NumField::Malformed), "1.2.3,"));
+        assert_eq!(rule.apply("1e,"), (Some(NumField::Malformed), "1e,"));
+    }
+
+    #[test]
+    fn test_float_lenient() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = Float::new(NumMode::Lenient);
+        let (out, rest) = rule.apply("1.25m");
+        assert_approx_eq!(f64, out.unwrap().value().unwrap(), 1.25);
+        assert_eq!(rest, "m");
+        let (out, rest) = rule.apply("2eX");
+        assert_approx_eq!(f64, out.unwrap().value().unwrap(), 2.0);
+        assert_eq!(rest, "eX");
+    }
+
+    #[test]
+    fn test_hex_byte() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = HexByte::default();
+        assert_eq!(rule.apply("5C\r\n"), (Some(NumField::Value(0x5C)), "\r\n"));
+        assert_eq!(rule.apply("0a"), (Some(NumField::Value(0x0A)), ""));
+        assert_eq!(rule.apply(""), (Some(NumField::Empty), ""));
+        assert_eq!(rule.apply("5"), (Some(NumField::Malformed), "5"));
+        assert_eq!(rule.apply("5G"), (Some(NumField::Malformed), "5G"));
+        assert_eq!(rule.apply("5CD"), (Some(NumField::Malformed), "5CD"));
+        let rule = HexByte::new(NumMode::Lenient);
+        assert_eq!(rule.apply("5CD"), (Some(NumField::Value(0x5C)), "D"));
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = FixedPoint::<3>::new(NumMode::Strict);
+        assert_eq!(
+            rule.apply("4807.038,N"),
+            (Some(NumField::Value(4807038)), ",N")
+        );
+        assert_eq!(rule.apply("12.5,"), (Some(NumField::Value(12500)), ","));
+        assert_eq!(rule.apply("-1,"), (Some(NumField::Value(-1000)), ","));
+        assert_eq!(rule.apply(".001"), (Some(NumField::Value(1)), ""));
+        assert_eq!(rule.apply(","), (Some(NumField::Empty), ","));
+        assert_eq!(
+            rule.apply("1.0001,"),
+            (Some(NumField::Malformed), "1.0001,")
+        );
+        assert_eq!(
+            rule.apply("99999999999999999999,"),
+            (Some(NumField::Malformed), "99999999999999999999,")
+        );
+        let rule = FixedPoint::<2>::new(NumMode::Lenient);
+        assert_eq!(rule.apply("1.239m"), (Some(NumField::Value(123)), "m"));
+    }
+
+    #[test]
+    fn test_num_field_accessors() {
+        assert_eq!(NumField::Value(1).value(), Some(1));
+        assert!(NumField::<u8>::Empty.is_empty());
+        assert!(NumField::<u8>::Malformed.is_malformed());
+        assert_eq!(NumField::<u8>::Malformed.value(), None);
+    }
+}
@@ -11,7 +11,7 @@ impl<'a, const N: usize> IRule for OneOfCharSet<'a, N> {
     fn name(&self) -> &str { "OneOfCharSet" }
 }
 
-impl<'a, const N: usize> IStrFlowRule<'a> for OneOfCharSet<'a, N> {
+impl<'a, 'f, const N: usize> IStrFlowRule<'a> for OneOfCharSet<'f, N> {
     type Output = char;
     /// Applies the OneOfCharSet rule to the input string.
     /// If the first character is in the set, returns the character and the rest
@@ -0,0 +1,105 @@
+use super::IBytesFlowRule;
+use crate::str_parser::rules::{IRule, UntilMode};
+
+/// Rule to extract everything from the input up to the first occurrence of a
+/// byte pattern, such as the sync bytes of a binary frame.
+/// Returns a tuple of (prefix, rest) if the pattern is found, otherwise
+/// returns None. With `UntilMode::KeepRight` the rest starts at the pattern,
+/// which skips any garbage in front of the next frame.
+pub struct UntilBytes {
+    pub pattern: &'static [u8],
+    pub mode: UntilMode,
+}
+
+impl IRule for UntilBytes {
+    fn name(&self) -> &str { "UntilBytes" }
+}
+
+impl<'a> IBytesFlowRule<'a> for UntilBytes {
+    type Output = &'a [u8];
+    fn apply(&self, input: &'a [u8]) -> (Option<&'a [u8]>, &'a [u8]) {
+        clerk::trace!(
+            "UntilBytes rule: input={:02X?}, pattern={:02X?}, mode={}",
+            input,
+            self.pattern,
+            self.mode
+        );
+        if self.pattern.is_empty() {
+            return (Some(&input[..0]), input);
+        }
+        match input
+            .windows(self.pattern.len())
+            .position(|w| w == self.pattern)
+        {
+            Some(idx) => {
+                let end = idx + self.pattern.len();
+                match self.mode {
+                    UntilMode::Discard => (Some(&input[..idx]), &input[end..]),
+                    UntilMode::KeepLeft => (Some(&input[..end]), &input[end..]),
+                    UntilMode::KeepRight => (Some(&input[..idx]), &input[idx..]),
+                }
+            }
+            None => {
+                clerk::debug!("UntilBytes: pattern {:02X?} not found", self.pattern);
+                (None, input)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+
+    const UBX_SYNC: &[u8] = &[0xB5, 0x62];
+
+    #[test]
+    fn test_until_bytes_keep_right() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = UntilBytes {
+            pattern: UBX_SYNC,
+            mode: UntilMode::KeepRight,
+        };
+        let (out, rest) = rule.apply(&[0x00, 0x11, 0xB5, 0x62, 0x01]);
+        assert_eq!(out, Some(&[0x00u8, 0x11][..]));
+        assert_eq!(rest, &[0xB5, 0x62, 0x01]);
+    }
+
+    #[test]
+    fn test_until_bytes_discard() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = UntilBytes {
+            pattern: UBX_SYNC,
+            mode: UntilMode::Discard,
+        };
+        let (out, rest) = rule.apply(&[0x00, 0xB5, 0x62, 0x01]);
+        assert_eq!(out, Some(&[0x00u8][..]));
+        assert_eq!(rest, &[0x01]);
+    }
+
+    #[test]
+    fn test_until_bytes_keep_left() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = UntilBytes {
+            pattern: UBX_SYNC,
+            mode: UntilMode::KeepLeft,
+        };
+        let (out, rest) = rule.apply(&[0x00, 0xB5, 0x62, 0x01]);
+        assert_eq!(out, Some(&[0x00u8, 0xB5, 0x62][..]));
+        assert_eq!(rest, &[0x01]);
+    }
+
+    #[test]
+    fn test_until_bytes_not_found() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = UntilBytes {
+            pattern: UBX_SYNC,
+            mode: UntilMode::KeepRight,
+        };
+        let (out, rest) = rule.apply(&[0x00, 0xB5]);
+        assert_eq!(out, None);
+        assert_eq!(rest, &[0x00, 0xB5]);
+    }
+}
@@ -1,4 +1,4 @@
-use super::IStrFlowRule;
+use super::{IStrFlowRule, IStrStreamRule, Partial};
 use crate::str_parser::IRule;
 use crate::str_parser::rules::UntilMode;
 
@@ -25,39 +25,38 @@ impl<'a, const C: char> IStrFlowRule<'a> for UntilChar<C> {
             C,
             self.mode
         );
-        for (i, c) in input.char_indices() {
-            if c == C {
-                match self.mode {
-                    UntilMode::Discard => {
-                        let end = i + C.len_utf8();
-                        clerk::debug!(
-                            "Until rule matched (include): prefix='{}', rest='{}'",
-                            &input[..i],
-                            &input[end..]
-                        );
-                        return (Some(&input[..i]), &input[end..]);
-                    }
-                    UntilMode::KeepLeft => {
-                        let end = i + C.len_utf8();
-                        clerk::debug!(
-                            "Until rule matched (include): prefix='{}', rest='{}'",
-                            &input[..end],
-                            &input[end..]
-                        );
-                        return (Some(&input[..end]), &input[end..]);
-                    }
-                    UntilMode::KeepRight => {
-                        clerk::debug!(
-                            "Until rule matched (include): prefix='{}', rest='{}'",
-                            &input[..i],
-                            &input[i..]
-                        );
-                        return (Some(&input[..i]), &input[i..]);
-                    }
-                }
-            }
+        let found = if C.is_ascii() {
+            memchr::memchr(C as u8, input.as_bytes())
+        } else {
+            input.find(C)
+        };
+        let Some(i) = found else {
+            return (None, input);
+        };
+        let end = i + C.len_utf8();
+        let (prefix, rest) = match self.mode {
+            UntilMode::Discard => (&input[..i], &input[end..]),
+            UntilMode::KeepLeft => (&input[..end], &input[end..]),
+            UntilMode::KeepRight => (&input[..i], &input[i..]),
+        };
+        clerk::debug!(
+            "Until rule matched: mode={}, prefix='{}', rest='{}'",
+            self.mode,
+            prefix,
+            rest
+        );
+        (Some(prefix), rest)
+    }
+}
+
+impl<'a, const C: char> IStrStreamRule<'a> for UntilChar<C> {
+    type Output = &'a str;
+    /// The delimiter may still arrive, so a miss is `Incomplete`.
+    fn apply_partial(&self, input: &'a str) -> (Partial<&'a str>, &'a str) {
+        match self.apply(input) {
+            (Some(out), rest) => (Partial::Done(out), rest),
+            (None, rest) => (Partial::Incomplete { needed: None }, rest),
         }
-        (None, input)
     }
 }
 
@@ -148,4 +147,17 @@ mod tests {
         assert_eq!(prefix, None);
         assert_eq!(rest, "");
     }
+
+    #[test]
+    fn test_until_partial() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = UntilChar::<','> {
+            mode: super::UntilMode::Discard,
+        };
+        assert_eq!(
+            rule.apply_partial("12"),
+            (Partial::Incomplete { needed: None }, "12")
+        );
+        assert_eq!(rule.apply_partial("12,3"), (Partial::Done("12"), "3"));
+    }
 }
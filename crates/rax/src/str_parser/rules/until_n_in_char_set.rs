@@ -1,6 +1,6 @@
 use super::IStrFlowRule;
 use crate::str_parser::IRule;
-use crate::str_parser::filters::{CharSetFilter, IFilter};
+use crate::str_parser::filters::CharSetFilter;
 use crate::str_parser::rules::UntilMode;
 
 /// Rule that extracts a prefix from the input string up to (but not including)
@@ -20,7 +20,7 @@ impl<'a, const N: usize, const M: usize> IRule for UntilNInCharSet<'a, N, M> {
     fn name(&self) -> &str { "UntilNInCharSet" }
 }
 
-impl<'a, const N: usize, const M: usize> IStrFlowRule<'a> for UntilNInCharSet<'a, N, M> {
+impl<'a, 'f, const N: usize, const M: usize> IStrFlowRule<'a> for UntilNInCharSet<'f, N, M> {
     type Output = &'a str;
 
     /// Applies the rule to the input string, returning the prefix up to the
@@ -30,42 +30,43 @@ impl<'a, const N: usize, const M: usize> IStrFlowRule<'a> for UntilNInCharSet<'a
     /// The `mode` determines whether the N-th matched character is included in
     /// the prefix, excluded, or kept as the first character of the rest.
     fn apply(&self, input: &'a str) -> (Option<&'a str>, &'a str) {
-        // How many more matches do we still need?
-        let mut remaining = N;
-
-        for (idx, ch) in input.char_indices() {
-            // Ask the user‑supplied filter whether this character is in the set.
-            if self.filter.filter(&ch) {
-                remaining -= 1;
-                if remaining == 0 {
-                    // `idx` points to the first byte of the N‑th match.
-                    // `after` points to the first byte *after* it.
-                    let after = idx + ch.len_utf8();
-                    let (prefix, rest) = match self.mode {
-                        UntilMode::Discard => (&input[..idx], &input[after..]),
-                        UntilMode::KeepLeft => (&input[..after], &input[after..]),
-                        UntilMode::KeepRight => (&input[..idx], &input[idx..]),
-                    };
-                    clerk::debug!(
-                        "UntilNInCharSet: mode={:?}, prefix='{}', rest='{}', idx={}, after={}, N={}",
-                        self.mode,
-                        prefix,
-                        rest,
-                        idx,
-                        after,
-                        N
-                    );
-                    return (Some(prefix), rest);
-                }
-            }
+        if N == 0 {
+            clerk::debug!("UntilNInCharSet: N is zero, returning None");
+            return (None, input);
+        }
+        // Jump from match to match; `from` is the byte after the last match.
+        let mut from = 0;
+        for _ in 0..N {
+            let Some(i) = self.filter.find_in(&input[from..]) else {
+                // Fewer than N occurrences found.
+                clerk::debug!(
+                    "UntilNInCharSet: fewer than {} matches found, returning None, input='{}'",
+                    N,
+                    input
+                );
+                return (None, input);
+            };
+            let idx = from + i;
+            from = idx + input[idx..].chars().next().map_or(0, char::len_utf8);
         }
-        // Fewer than N occurrences found.
+        // `from` points to the first byte *after* the N-th match.
+        let after = from;
+        let idx = after - input[..after].chars().next_back().map_or(0, char::len_utf8);
+        let (prefix, rest) = match self.mode {
+            UntilMode::Discard => (&input[..idx], &input[after..]),
+            UntilMode::KeepLeft => (&input[..after], &input[after..]),
+            UntilMode::KeepRight => (&input[..idx], &input[idx..]),
+        };
         clerk::debug!(
-            "UntilNInCharSet: fewer than {} matches found, returning None, input='{}'",
-            N,
-            input
+            "UntilNInCharSet: mode={:?}, prefix='{}', rest='{}', idx={}, after={}, N={}",
+            self.mode,
+            prefix,
+            rest,
+            idx,
+            after,
+            N
         );
-        (None, input)
+        (Some(prefix), rest)
     }
 }
 
@@ -1,6 +1,6 @@
 use super::IStrFlowRule;
 use crate::str_parser::IRule;
-use crate::str_parser::filters::{CharSetFilter, IFilter};
+use crate::str_parser::filters::CharSetFilter;
 use crate::str_parser::rules::UntilMode;
 
 /// Rule that extracts a prefix from the input string up to (but not including)
@@ -20,7 +20,7 @@ impl<'a, const N: usize> IRule for UntilNotInCharSet<'a, N> {
     fn name(&self) -> &str { "UntilNotInCharSet" }
 }
 
-impl<'a, const N: usize> IStrFlowRule<'a> for UntilNotInCharSet<'a, N> {
+impl<'a, 'f, const N: usize> IStrFlowRule<'a> for UntilNotInCharSet<'f, N> {
     type Output = &'a str;
 
     /// Applies the rule to the input string, returning the prefix of characters
@@ -29,56 +29,28 @@ impl<'a, const N: usize> IStrFlowRule<'a> for UntilNotInCharSet<'a, N> {
     /// If `include` is true, the first character not in the set is included in
     /// the prefix.
     fn apply(&self, input: &'a str) -> (Option<&'a str>, &'a str) {
-        // Iterate over each character and its byte index in the input string
-        for (i, c) in input.char_indices() {
-            // If the character is NOT in the set, split here
-            if !self.filter.filter(&c) {
-                match self.mode {
-                    UntilMode::Discard => {
-                        let prefix = &input[..i];
-                        let rest = &input[i + c.len_utf8()..];
-                        clerk::debug!(
-                            "UntilNotInCharSet(include): prefix='{}', rest='{}', i={}, c='{}'",
-                            prefix,
-                            rest,
-                            i,
-                            c
-                        );
-                        return (Some(prefix), rest);
-                    }
-                    UntilMode::KeepLeft => {
-                        let prefix = &input[..i + c.len_utf8()];
-                        let rest = &input[i + c.len_utf8()..];
-                        clerk::debug!(
-                            "UntilNotInCharSet(include): prefix='{}', rest='{}', i={}, c='{}'",
-                            prefix,
-                            rest,
-                            i,
-                            c
-                        );
-                        return (Some(prefix), rest);
-                    }
-                    UntilMode::KeepRight => {
-                        let prefix = &input[..i];
-                        let rest = &input[i..];
-                        clerk::debug!(
-                            "UntilNotInCharSet(not include): prefix='{}', rest='{}', i={}, c='{}'",
-                            prefix,
-                            rest,
-                            i,
-                            c
-                        );
-                        return (Some(prefix), rest);
-                    }
-                }
-            }
-        }
-        // If all characters are in the set, return None and the original input
+        let Some(i) = self.filter.find_not_in(input) else {
+            // If all characters are in the set, return None and the original input
+            clerk::debug!(
+                "UntilNotInCharSet: all characters in set, returning None, input='{}'",
+                input
+            );
+            return (None, input);
+        };
+        let end = i + input[i..].chars().next().map_or(0, char::len_utf8);
+        let (prefix, rest) = match self.mode {
+            UntilMode::Discard => (&input[..i], &input[end..]),
+            UntilMode::KeepLeft => (&input[..end], &input[end..]),
+            UntilMode::KeepRight => (&input[..i], &input[i..]),
+        };
         clerk::debug!(
-            "UntilNotInCharSet: all characters in set, returning None, input='{}'",
-            input
+            "UntilNotInCharSet: mode={}, prefix='{}', rest='{}', i={}",
+            self.mode,
+            prefix,
+            rest,
+            i
         );
-        (None, input)
+        (Some(prefix), rest)
     }
 }
 
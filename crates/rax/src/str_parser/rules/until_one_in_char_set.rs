@@ -1,6 +1,6 @@
-use super::IStrFlowRule;
+use super::{IStrFlowRule, IStrStreamRule, Partial};
 use crate::str_parser::IRule;
-use crate::str_parser::filters::{CharSetFilter, IFilter};
+use crate::str_parser::filters::CharSetFilter;
 use crate::str_parser::rules::UntilMode;
 
 /// Rule to extract everything from the input string up to (but not including)
@@ -18,7 +18,7 @@ impl<'a, const N: usize> IRule for UntilOneInCharSet<'a, N> {
     fn name(&self) -> &str { "UntilOneInCharSet" }
 }
 
-impl<'a, const N: usize> IStrFlowRule<'a> for UntilOneInCharSet<'a, N> {
+impl<'a, 'f, const N: usize> IStrFlowRule<'a> for UntilOneInCharSet<'f, N> {
     type Output = &'a str;
 
     /// Applies the UntilOneInCharSet rule to the input string.
@@ -29,59 +29,38 @@ impl<'a, const N: usize> IStrFlowRule<'a> for UntilOneInCharSet<'a, N> {
     /// None. If no character in the set is found, returns None and the
     /// original input.
     fn apply(&self, input: &'a str) -> (Option<&'a str>, &'a str) {
-        for (i, c) in input.char_indices() {
-            if self.filter.filter(&c) {
-                match self.mode {
-                    UntilMode::Discard => {
-                        // Include the matched character in the prefix
-                        let prefix = &input[..i];
-                        let rest = &input[i + c.len_utf8()..];
-                        clerk::debug!(
-                            "UntilOneInCharSet(include): prefix='{}', rest='{}', i={}, c='{}'",
-                            prefix,
-                            rest,
-                            i,
-                            c
-                        );
-                        return (Some(prefix), rest);
-                    }
-                    UntilMode::KeepLeft => {
-                        // Include the matched character in the prefix
-                        let end_of_char = i + c.len_utf8();
-                        let prefix = &input[..end_of_char];
-                        let rest = &input[end_of_char..];
-                        clerk::debug!(
-                            "UntilOneInCharSet(include): prefix='{}', rest='{}', i={}, c='{}'",
-                            prefix,
-                            rest,
-                            i,
-                            c
-                        );
-                        return (Some(prefix), rest);
-                    }
-
-                    UntilMode::KeepRight => {
-                        // Not include, and not first char
-                        let prefix = &input[..i];
-                        let rest = &input[i..];
-                        clerk::debug!(
-                            "UntilOneInCharSet(not include): prefix='{}', rest='{}', i={}, c='{}'",
-                            prefix,
-                            rest,
-                            i,
-                            c
-                        );
-                        return (Some(prefix), rest);
-                    }
-                }
-            }
-        }
-        // No character in the set found
+        let Some(i) = self.filter.find_in(input) else {
+            clerk::debug!(
+                "UntilOneInCharSet: no match found, returning None, input='{}'",
+                input
+            );
+            return (None, input);
+        };
+        let end = i + input[i..].chars().next().map_or(0, char::len_utf8);
+        let (prefix, rest) = match self.mode {
+            UntilMode::Discard => (&input[..i], &input[end..]),
+            UntilMode::KeepLeft => (&input[..end], &input[end..]),
+            UntilMode::KeepRight => (&input[..i], &input[i..]),
+        };
         clerk::debug!(
-            "UntilOneInCharSet: no match found, returning None, input='{}'",
-            input
+            "UntilOneInCharSet: mode={}, prefix='{}', rest='{}', i={}",
+            self.mode,
+            prefix,
+            rest,
+            i
         );
-        (None, input)
+        (Some(prefix), rest)
+    }
+}
+
+impl<'a, 'f, const N: usize> IStrStreamRule<'a> for UntilOneInCharSet<'f, N> {
+    type Output = &'a str;
+    /// A character of the set may still arrive, so a miss is `Incomplete`.
+    fn apply_partial(&self, input: &'a str) -> (Partial<&'a str>, &'a str) {
+        match self.apply(input) {
+            (Some(out), rest) => (Partial::Done(out), rest),
+            (None, rest) => (Partial::Incomplete { needed: None }, rest),
+        }
     }
 }
 
@@ -169,4 +148,18 @@ mod tests {
         assert_eq!(prefix, None);
         assert_eq!(rest, "");
     }
+
+    #[test]
+    fn test_until_one_in_char_set_partial() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = UntilOneInCharSet {
+            filter: &DIGITS,
+            mode: UntilMode::Discard,
+        };
+        assert_eq!(
+            rule.apply_partial("abc"),
+            (Partial::Incomplete { needed: None }, "abc")
+        );
+        assert_eq!(rule.apply_partial("ab1c"), (Partial::Done("ab"), "c"));
+    }
 }
@@ -1,4 +1,4 @@
-use super::IStrFlowRule;
+use super::{IStrFlowRule, IStrStreamRule, Partial};
 use crate::str_parser::IRule;
 use crate::str_parser::rules::UntilMode;
 
@@ -31,7 +31,7 @@ impl<'a> IStrFlowRule<'a> for UntilStr {
             self.pattern,
             self.mode
         );
-        match input.find(self.pattern) {
+        match memchr::memmem::find(input.as_bytes(), self.pattern.as_bytes()) {
             Some(idx) => match self.mode {
                 UntilMode::Discard => {
                     let end = idx + self.pattern.len();
@@ -72,6 +72,17 @@ impl<'a> IStrFlowRule<'a> for UntilStr {
     }
 }
 
+impl<'a> IStrStreamRule<'a> for UntilStr {
+    type Output = &'a str;
+    /// The delimiter may still arrive, so a miss is `Incomplete`.
+    fn apply_partial(&self, input: &'a str) -> (Partial<&'a str>, &'a str) {
+        match self.apply(input) {
+            (Some(out), rest) => (Partial::Done(out), rest),
+            (None, rest) => (Partial::Incomplete { needed: None }, rest),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use clerk::{LogLevel, init_log_with_level};
@@ -155,4 +166,18 @@ mod tests {
         assert_eq!(prefix, None);
         assert_eq!(rest, "");
     }
+
+    #[test]
+    fn test_until_partial() {
+        init_log_with_level(LogLevel::TRACE);
+        let rule = UntilStr {
+            pattern: "\r\n",
+            mode: UntilMode::Discard,
+        };
+        assert_eq!(
+            rule.apply_partial("ab\r"),
+            (Partial::Incomplete { needed: None }, "ab\r")
+        );
+        assert_eq!(rule.apply_partial("ab\r\nc"), (Partial::Done("ab"), "c"));
+    }
 }
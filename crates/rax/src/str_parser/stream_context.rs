@@ -0,0 +1,235 @@
+use crate::str_parser::rules::{IStrStreamRule, Partial};
+
+/// Parser context for input that arrives in chunks, e.g. straight off a
+/// serial port.
+///
+/// Bytes are appended with `feed`; rules implementing [`IStrStreamRule`] are
+/// applied at the cursor and only advance it on `Done`, so an `Incomplete`
+/// take can simply be retried after the next chunk. Consumed input is
+/// dropped on every `feed`, and the internal buffer is reused, so memory is
+/// bounded by the longest unconsumed span (or by `with_limit`).
+pub struct StrStreamContext {
+    buf: String,
+    pending: Vec<u8>,
+    pos: usize,
+    limit: Option<usize>,
+    eof: bool,
+}
+
+impl Default for StrStreamContext {
+    fn default() -> Self { Self::new() }
+}
+
+impl StrStreamContext {
+    pub fn new() -> Self {
+        Self {
+            buf: String::new(),
+            pending: Vec::new(),
+            pos: 0,
+            limit: None,
+            eof: false,
+        }
+    }
+    /// Create a context that refuses to buffer more than `max_bytes`.
+    pub fn with_limit(max_bytes: usize) -> Self {
+        Self {
+            limit: Some(max_bytes),
+            ..Self::new()
+        }
+    }
+
+    /// Append a chunk of bytes. A UTF-8 sequence split across chunks is held
+    /// back until it is complete.
+    ///
+    /// A chunk that would exceed the limit is rejected as a whole. On invalid
+    /// UTF-8 the input before the bad sequence is kept and the rest of the
+    /// chunk is dropped.
+    pub fn feed(&mut self, chunk: &[u8]) -> miette::Result<&mut Self> {
+        if self.eof {
+            miette::bail!("cannot feed a finished stream");
+        }
+        self.compact();
+        if let Some(limit) = self.limit
+            && self.buf.len() + self.pending.len() + chunk.len() > limit
+        {
+            miette::bail!(
+                "stream buffer exceeds limit of {} bytes without being consumed",
+                limit
+            );
+        }
+        self.pending.extend_from_slice(chunk);
+        let (valid, invalid) = match std::str::from_utf8(&self.pending) {
+            Ok(s) => (s.len(), false),
+            Err(e) => (e.valid_up_to(), e.error_len().is_some()),
+        };
+        // `valid` bytes were just checked by `from_utf8`.
+        self.buf
+            .push_str(std::str::from_utf8(&self.pending[..valid]).unwrap_or_default());
+        self.pending.drain(..valid);
+        if invalid {
+            self.pending.clear();
+            miette::bail!("invalid UTF-8 in stream at byte {}", self.buf.len());
+        }
+        clerk::trace!(
+            "StrStreamContext::feed: {} bytes buffered, {} pending",
+            self.buf.len() - self.pos,
+            self.pending.len()
+        );
+        Ok(self)
+    }
+    /// Mark the end of the stream; from now on `Incomplete` becomes `Fail`.
+    pub fn finish(&mut self) -> &mut Self {
+        self.eof = true;
+        self
+    }
+    pub fn is_finished(&self) -> bool { self.eof }
+
+    /// Unconsumed, complete input.
+    pub fn rest_str(&self) -> &str { &self.buf[self.pos..] }
+    /// Number of buffered bytes that have not been consumed yet.
+    pub fn buffered(&self) -> usize { self.buf.len() - self.pos + self.pending.len() }
+
+    /// Drop consumed input from the front of the buffer.
+    fn compact(&mut self) {
+        if self.pos > 0 {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+        }
+    }
+}
+
+impl StrStreamContext {
+    /// Apply `rule` at the cursor. The cursor only moves on `Done`.
+    pub fn take<'a, R>(&'a mut self, rule: &R) -> Partial<R::Output>
+    where
+        R: IStrStreamRule<'a>,
+    {
+        let input = &self.buf[self.pos..];
+        match rule.apply_partial(input) {
+            (Partial::Done(out), rest) => {
+                self.pos = self.buf.len() - rest.len();
+                Partial::Done(out)
+            }
+            (Partial::Incomplete { .. }, _) if self.eof => {
+                clerk::debug!("StrStreamContext: `{}` incomplete at EOF", rule.name());
+                Partial::Fail
+            }
+            (other, _) => other,
+        }
+    }
+    /// Like `take`, discarding the output.
+    pub fn skip<R>(&mut self, rule: &R) -> Partial<()>
+    where
+        R: for<'a> IStrStreamRule<'a>,
+    {
+        match self.take(rule) {
+            Partial::Done(_) => Partial::Done(()),
+            Partial::Incomplete { needed } => Partial::Incomplete { needed },
+            Partial::Fail => Partial::Fail,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clerk::{LogLevel, init_log_with_level};
+
+    use super::*;
+    use crate::str_parser::rules::{Char, CharCount, UntilChar, UntilMode};
+
+    const UNTIL_COMMA: UntilChar<','> = UntilChar {
+        mode: UntilMode::Discard,
+    };
+
+    #[test]
+    fn test_feed_and_resume() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrStreamContext::new();
+        ctx.feed(b"$GPG")?;
+        assert_eq!(ctx.take(&Char::<'$'>), Partial::Done('$'));
+        assert_eq!(ctx.take(&UNTIL_COMMA), Partial::Incomplete { needed: None });
+        assert_eq!(ctx.rest_str(), "GPG");
+        ctx.feed(b"GA,1235")?;
+        assert_eq!(ctx.take(&UNTIL_COMMA), Partial::Done("GPGGA"));
+        assert_eq!(ctx.take(&UNTIL_COMMA), Partial::Incomplete { needed: None });
+        ctx.feed(b"19,")?;
+        assert_eq!(ctx.take(&UNTIL_COMMA), Partial::Done("123519"));
+        assert_eq!(ctx.buffered(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_utf8() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrStreamContext::new();
+        let bytes = "你好".as_bytes();
+        ctx.feed(&bytes[..2])?;
+        assert_eq!(ctx.rest_str(), "");
+        assert_eq!(ctx.buffered(), 2);
+        ctx.feed(&bytes[2..])?;
+        assert_eq!(ctx.take(&CharCount::<2>), Partial::Done("你好"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrStreamContext::new();
+        assert!(ctx.feed(&[b'a', 0xFF, b'b']).is_err());
+    }
+
+    #[test]
+    fn test_invalid_utf8_keeps_prefix() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrStreamContext::new();
+        let bytes = "你".as_bytes();
+        ctx.feed(&bytes[..1])?;
+        assert!(
+            ctx.feed(&[bytes[1], bytes[2], b'$', b'G', b'P', 0xFF, b'G'])
+                .is_err()
+        );
+        assert_eq!(ctx.take(&CharCount::<1>), Partial::Done("你"));
+        assert_eq!(ctx.rest_str(), "$GP");
+
+        let mut ctx = StrStreamContext::new();
+        assert!(ctx.feed(b"$GP\xffGGA").is_err());
+        assert_eq!(ctx.take(&Char::<'$'>), Partial::Done('$'));
+        assert_eq!(ctx.rest_str(), "GP");
+        ctx.feed(b"GGA,")?;
+        assert_eq!(ctx.take(&UNTIL_COMMA), Partial::Done("GPGGA"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrStreamContext::with_limit(4);
+        ctx.feed(b"abc")?;
+        assert!(ctx.feed(b"de").is_err());
+        assert_eq!(ctx.rest_str(), "abc");
+        ctx.skip(&CharCount::<3>);
+        ctx.feed(b"de")?;
+        assert_eq!(ctx.rest_str(), "de");
+
+        // a held-back sequence survives a rejected chunk
+        let bytes = "你".as_bytes();
+        let mut ctx = StrStreamContext::with_limit(4);
+        ctx.feed(&bytes[..2])?;
+        assert!(ctx.feed(b"abc").is_err());
+        ctx.feed(&bytes[2..])?;
+        assert_eq!(ctx.rest_str(), "你");
+        Ok(())
+    }
+
+    #[test]
+    fn test_finish() -> miette::Result<()> {
+        init_log_with_level(LogLevel::TRACE);
+        let mut ctx = StrStreamContext::new();
+        ctx.feed(b"abc")?;
+        assert!(ctx.take(&UNTIL_COMMA).is_incomplete());
+        ctx.finish();
+        assert_eq!(ctx.take(&UNTIL_COMMA), Partial::Fail);
+        assert!(ctx.feed(b",").is_err());
+        Ok(())
+    }
+}
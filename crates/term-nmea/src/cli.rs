@@ -16,6 +16,10 @@ pub struct CliArgs {
     #[arg(short, long)]
     pub capacity: Option<usize>,
 
+    /// Probe common baud rates and use the one the receiver talks at
+    #[arg(long)]
+    pub detect_baud: bool,
+
     #[command(flatten)]
     pub verbose: clap_verbosity_flag::Verbosity,
 }
@@ -30,6 +34,7 @@ mod tests {
         assert_eq!(args.port, None);
         assert_eq!(args.baud_rate, None);
         assert_eq!(args.capacity, None);
+        assert!(!args.detect_baud);
     }
 
     #[test]
@@ -50,6 +50,7 @@ async fn main() -> miette::Result<()> {
     tokio::spawn(serial::start_serial_reader(
         SETTINGS.get().unwrap().port.clone(),
         SETTINGS.get().unwrap().baud_rate,
+        SETTINGS.get().unwrap().device.clone(),
         tx,
     ));
 
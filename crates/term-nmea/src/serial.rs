@@ -1,42 +1,83 @@
-use miette::{Context, IntoDiagnostic};
-use rax::io::{AsyncIRaxReader, AsyncRaxReader};
+use rax::device::{BaudProbe, DeviceFilterExpr, list_devices};
+use rax::io::{
+    Framing, IAsyncConnector, InvalidUtf8, ReaderEvent, ReconnectPolicy, ResilientReader,
+    SerialConnector,
+};
 use rax_nmea::Dispatcher;
 use rax_nmea::data::{Identifier, Talker};
-use tokio::io::BufReader;
+use rax_nmea::rules::NMEA_VALIDATE;
 use tokio::sync::mpsc::Sender;
-use tokio_serial::SerialPortBuilderExt;
+
+/// Port to open: the first device matching `device`, or `port` when no
+/// filter is configured. Resolved on every connect, so a receiver that
+/// comes back under another name is found again.
+fn resolve_port(port: &str, device: Option<&DeviceFilterExpr>) -> miette::Result<String> {
+    let Some(device) = device else {
+        return Ok(port.to_string());
+    };
+    match list_devices(|p| device.matches(p))?.into_iter().next() {
+        Some(found) => Ok(found.name().to_string()),
+        None => miette::bail!("no serial device matches {device:?}"),
+    }
+}
+
+/// Probe the configured port for the baud rate its receiver talks at.
+pub fn detect_baud_rate(port: &str, device: Option<&DeviceFilterExpr>) -> miette::Result<u32> {
+    let path = resolve_port(port, device)?;
+    let result = BaudProbe::new().probe(&path, &NMEA_VALIDATE)?;
+    match result.best() {
+        Some(best) => {
+            clerk::info!(
+                "Detected {} baud on '{path}' ({:?})",
+                best.baud_rate,
+                best.protocols
+            );
+            Ok(best.baud_rate)
+        }
+        None => miette::bail!("no NMEA data on '{path}' at any probed baud rate"),
+    }
+}
 
 pub async fn start_serial_reader(
     port: String,
     baud_rate: u32,
+    device: Option<DeviceFilterExpr>,
     tx: Sender<(Talker, Identifier, String)>,
 ) -> miette::Result<()> {
-    if !tokio_serial::available_ports()
-        .into_diagnostic()?
-        .iter()
-        .any(|p| p.port_name.eq_ignore_ascii_case(&port))
-    {
-        let msg = format!("Port '{port}' is not available");
-        clerk::error!("{msg}");
-        if !cfg!(debug_assertions) {
-            eprintln!("{msg}");
-            std::process::exit(1);
+    let connector = {
+        let port = port.clone();
+        move || {
+            let path = resolve_port(&port, device.as_ref());
+            async move { SerialConnector::new(path?, baud_rate).connect().await }
         }
-    }
-
-    let serial = tokio_serial::new(port.clone(), baud_rate)
-        .open_native_async()
-        .into_diagnostic()
-        .wrap_err_with(|| format!("Failed to open serial port: {port}"))?;
-    let mut reader = AsyncRaxReader::new(BufReader::new(serial));
+    };
+    let framing = Framing::lines().invalid_utf8(InvalidUtf8::Replace);
+    let mut reader = ResilientReader::with_framing(connector, ReconnectPolicy::default(), framing);
     let mut dispatcher = Dispatcher::new();
     loop {
-        if let Some(msg) = reader
-            .read_line()
-            .await?
-            .and_then(|l| dispatcher.dispatch(l))
-        {
-            let _ = tx.send(msg).await;
+        match reader.next_event().await? {
+            ReaderEvent::Line(line) => {
+                if let Some(msg) = dispatcher.dispatch(line) {
+                    let _ = tx.send(msg).await;
+                }
+            }
+            ReaderEvent::Connected => clerk::info!("Serial port '{port}' connected"),
+            ReaderEvent::ConnectFailed {
+                attempt,
+                reason,
+                retry_in,
+            } => clerk::warn!(
+                "Failed to open serial port '{port}' (attempt {attempt}): {reason}, retrying in {retry_in:?}"
+            ),
+            ReaderEvent::Disconnected { reason } => {
+                clerk::warn!("Serial port '{port}' disconnected: {reason}");
+                // Drop half-received multi-line sentences.
+                dispatcher = Dispatcher::new();
+            }
+            ReaderEvent::IdleTimeout => {
+                clerk::warn!("No data from serial port '{port}', reconnecting");
+                dispatcher = Dispatcher::new();
+            }
         }
     }
 }
@@ -5,6 +5,7 @@ use std::{fs, io};
 use clap_verbosity_flag::VerbosityFilter;
 use clerk::LogLevel;
 use miette::IntoDiagnostic;
+use rax::device::DeviceFilterExpr;
 use serde::{Deserialize, Serialize};
 
 use crate::cli::CliArgs;
@@ -19,6 +20,9 @@ pub struct Settings {
     pub verbose: LogLevel,
 
     pub tab_coord: TabCoordSettings,
+    /// Open the first device matching this filter instead of `port`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<DeviceFilterExpr>,
 }
 
 impl Default for Settings {
@@ -32,6 +36,7 @@ impl Default for Settings {
             tab_coord: TabCoordSettings {
                 custom_cs: String::default(),
             },
+            device: None,
         }
     }
 }
@@ -64,6 +69,12 @@ impl Settings {
         if let Some(cap) = cli.capacity {
             settings.capacity = cap;
         }
+        if cli.detect_baud {
+            match crate::serial::detect_baud_rate(&settings.port, settings.device.as_ref()) {
+                Ok(baud) => settings.baud_rate = baud,
+                Err(e) => clerk::warn!("Baud rate detection failed: {e}"),
+            }
+        }
         settings.verbose = match cli.verbose.filter() {
             VerbosityFilter::Error => LogLevel::ERROR,
             VerbosityFilter::Warn => LogLevel::WARN,